@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use prettytable::{row, Table};
+
+use crate::action::run::combine::RuntimeConfigCombine;
+use crate::app;
+use crate::app::AppError;
+use crate::config::{ProjectConfig, TmsConfig};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Level {
+    Pass,
+    Warn,
+    Fail,
+}
+
+impl Level {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Level::Pass => "PASS",
+            Level::Warn => "WARN",
+            Level::Fail => "FAIL",
+        }
+    }
+}
+
+struct Check {
+    project: String,
+    name: String,
+    level: Level,
+    detail: String,
+}
+
+struct Report {
+    checks: Vec<Check>,
+}
+
+impl Report {
+    fn add(&mut self, project: &str, name: &str, level: Level, detail: String) {
+        self.checks.push(Check {
+            project: project.to_string(),
+            name: name.to_string(),
+            level,
+            detail,
+        });
+    }
+}
+
+pub fn doctor(config: &TmsConfig) -> Result<i32, AppError> {
+    let mut report = Report { checks: Vec::new() };
+    // port -> (project, port name)
+    let mut used_ports: BTreeMap<u32, Vec<(String, &str)>> = BTreeMap::new();
+    for project in &config.projects {
+        let runtime = match RuntimeConfigCombine::for_project(config, project, None, None) {
+            Ok(runtime) => runtime,
+            Err(e) => {
+                report.add(&project.name, "runtime", Level::Fail, e.to_string());
+                continue;
+            }
+        };
+        check_java(&mut report, project, &runtime);
+        check_tomcat(&mut report, project, &runtime);
+        check_items(&mut report, project);
+        for (name, port) in check_ports(&mut report, project, &runtime) {
+            used_ports
+                .entry(port)
+                .or_default()
+                .push((project.name.clone(), name));
+        }
+    }
+
+    for (port, users) in &used_ports {
+        let mut projects: Vec<&str> = users.iter().map(|t| t.0.as_str()).collect();
+        projects.dedup();
+        if projects.len() > 1 {
+            let names: Vec<String> = users.iter().map(|t| format!("{}.{}", t.0, t.1)).collect();
+            report.add(
+                projects.join(", ").as_str(),
+                "port collision",
+                Level::Warn,
+                format!(
+                    "{} is used by {}, these projects cannot run at the same time",
+                    port,
+                    names.join(", ")
+                ),
+            );
+        }
+    }
+
+    let mut table = Table::new();
+    table.add_row(row!["Project", "Check", "Status", "Detail"]);
+    for check in &report.checks {
+        table.add_row(row![
+            check.project,
+            check.name,
+            check.level.as_str(),
+            check.detail
+        ]);
+    }
+    table.printstd();
+
+    let count = |level: Level| report.checks.iter().filter(|t| t.level == level).count();
+    let failures = count(Level::Fail);
+    println!(
+        "{} passed, {} warnings, {} failed",
+        count(Level::Pass),
+        count(Level::Warn),
+        failures
+    );
+    Ok(if failures > 0 { 1 } else { 0 })
+}
+
+fn check_java(report: &mut Report, project: &ProjectConfig, runtime: &RuntimeConfigCombine) {
+    let java_home = match runtime.java_home() {
+        Ok(java_home) => java_home,
+        Err(e) => {
+            report.add(&project.name, "java_home", Level::Fail, e.to_string());
+            return;
+        }
+    };
+    report.add(&project.name, "java_home", Level::Pass, java_home.clone());
+    match app::version::java_version(Path::new(&java_home)) {
+        Ok(version) => report.add(&project.name, "java version", Level::Pass, version),
+        Err(e) => report.add(&project.name, "java version", Level::Fail, e.to_string()),
+    }
+}
+
+fn check_tomcat(report: &mut Report, project: &ProjectConfig, runtime: &RuntimeConfigCombine) {
+    let tomcat_home = match runtime.tomcat_home() {
+        Ok(tomcat_home) => tomcat_home,
+        Err(e) => {
+            report.add(&project.name, "tomcat_home", Level::Fail, e.to_string());
+            return;
+        }
+    };
+    report.add(
+        &project.name,
+        "tomcat_home",
+        Level::Pass,
+        tomcat_home.clone(),
+    );
+    let server_xml = PathBuf::from(&tomcat_home)
+        .join("conf")
+        .join(app::constant::SERVER_XML);
+    if !server_xml.exists() {
+        report.add(
+            &project.name,
+            "server.xml",
+            Level::Fail,
+            format!("{} does not exist", server_xml.display()),
+        );
+    }
+    match app::version::tomcat_version(Path::new(&tomcat_home)) {
+        Ok(version) => report.add(&project.name, "tomcat version", Level::Pass, version),
+        Err(e) => report.add(&project.name, "tomcat version", Level::Warn, e.to_string()),
+    }
+}
+
+fn check_items(report: &mut Report, project: &ProjectConfig) {
+    for item in &project.items {
+        let check_name = format!("item '{}'", item.name);
+        let path = PathBuf::from(&item.path);
+        let (level, detail) = if !path.exists() {
+            (
+                Level::Fail,
+                format!(
+                    "{} does not exist (declared in {})",
+                    path.display(),
+                    project.source.display()
+                ),
+            )
+        } else if path.is_file() {
+            let is_war = path
+                .extension()
+                .map(|t| t.eq_ignore_ascii_case("war"))
+                .unwrap_or(false);
+            if is_war {
+                (Level::Pass, format!("WAR {}", path.display()))
+            } else {
+                (Level::Fail, format!("{} is not a WAR file", path.display()))
+            }
+        } else if path.join("WEB-INF").is_dir() {
+            (Level::Pass, format!("exploded webapp {}", path.display()))
+        } else {
+            (
+                Level::Warn,
+                format!("{} has no WEB-INF directory", path.display()),
+            )
+        };
+        report.add(&project.name, check_name.as_str(), level, detail);
+    }
+}
+
+fn check_ports(
+    report: &mut Report,
+    project: &ProjectConfig,
+    runtime: &RuntimeConfigCombine,
+) -> Vec<(&'static str, u32)> {
+    let mut ports = Vec::new();
+    let mut checks = vec![
+        ("http_port", runtime.http_port()),
+        ("server_port", runtime.server_port()),
+        ("jpda_port", runtime.jpda_port()),
+    ];
+    match runtime.https_port() {
+        Ok(None) => {}
+        Ok(Some(port)) => checks.push(("https_port", Ok(port))),
+        Err(e) => checks.push(("https_port", Err(e))),
+    }
+    for (name, port) in checks {
+        match port {
+            Ok(port) => {
+                if let Some((other, _)) = ports.iter().find(|(_, t)| *t == port) {
+                    report.add(
+                        &project.name,
+                        name,
+                        Level::Fail,
+                        format!("{} is the same as {}", port, other),
+                    );
+                } else {
+                    report.add(&project.name, name, Level::Pass, port.to_string());
+                }
+                ports.push((name, port));
+            }
+            Err(e) => report.add(&project.name, name, Level::Fail, e.to_string()),
+        }
+    }
+    ports
+}
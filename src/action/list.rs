@@ -1,6 +1,7 @@
 use prettytable::{row, Table};
 
 use crate::config::TmsConfig;
+use crate::app;
 use crate::app::AppError;
 
 pub fn list_projects(config: &TmsConfig) {
@@ -23,7 +24,7 @@ pub fn list_projects(config: &TmsConfig) {
 
 pub fn list_project_items(config: &TmsConfig, project_name: &str) -> Result<(), AppError> {
     let mut table = Table::new();
-    table.add_row(row!["Project", "Item", "Alias", "Context Path"]);
+    table.add_row(row!["Project", "Item", "Alias", "Context Path", "Path"]);
     let project = super::get_project(config, project_name)?;
     let project_name = project.name.as_str();
     for item in &project.items {
@@ -33,8 +34,37 @@ pub fn list_project_items(config: &TmsConfig, project_name: &str) -> Result<(),
             None => "",
         };
         let context_path = item.context_path.as_str();
-        table.add_row(row![project_name, name, alias, context_path]);
+        let path = item.path.as_str();
+        table.add_row(row![project_name, name, alias, context_path, path]);
     }
     table.printstd();
     Ok(())
 }
+
+pub fn list_jdks() {
+    let jdks = app::jdk::discover();
+    if jdks.is_empty() {
+        println!("No JDK found");
+        return;
+    }
+    let mut table = Table::new();
+    table.add_row(row!["Version", "Vendor", "Path"]);
+    for jdk in &jdks {
+        table.add_row(row![jdk.version, jdk.vendor, jdk.home.display()]);
+    }
+    table.printstd();
+}
+
+pub fn list_tomcats() {
+    let tomcats = app::tomcat::installed();
+    if tomcats.is_empty() {
+        println!("No Tomcat installed, install one with `tms tomcat add <archive>`");
+        return;
+    }
+    let mut table = Table::new();
+    table.add_row(row!["Version", "Path"]);
+    for tomcat in &tomcats {
+        table.add_row(row![tomcat.version, tomcat.home.display()]);
+    }
+    table.printstd();
+}
@@ -1,9 +1,13 @@
-use crate::config::{ProjectConfig, ProjectItemConfig, TmsConfig};
+use crate::config::{ProjectConfig, ProjectEnvConfig, ProjectItemConfig, TmsConfig};
 use crate::app::AppError;
 
+pub mod doctor;
 pub mod list;
 pub mod run;
 pub mod clean;
+pub mod state;
+pub mod stop;
+pub mod status;
 
 fn filter_by_name(target_name: &str, name: &str, alias: Option<&String>) -> bool {
     if target_name == name {
@@ -34,6 +38,24 @@ fn get_project<'a>(
     }
 }
 
+fn get_project_env<'a>(
+    config: &'a ProjectConfig,
+    env_name: &str,
+) -> Result<&'a ProjectEnvConfig, AppError> {
+    match config.envs.get(env_name) {
+        None => {
+            let envs: Vec<&str> = config.envs.keys().map(|t| t.as_str()).collect();
+            Err(AppError::Action(format!(
+                "Env '{}' of project '{}' not found, available: [{}]",
+                env_name,
+                config.name.as_str(),
+                envs.join(", ")
+            )))
+        }
+        Some(env) => Ok(env),
+    }
+}
+
 fn get_project_item<'a>(
     config: &'a ProjectConfig,
     item_name: &str,
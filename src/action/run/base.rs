@@ -0,0 +1,175 @@
+use std::path::{Path, PathBuf};
+
+use crate::app::AppError;
+use crate::config::ProjectConfig;
+
+#[cfg(not(windows))]
+const SETENV: &str = "setenv.sh";
+#[cfg(windows)]
+const SETENV: &str = "setenv.bat";
+
+// shared libs of the project are put into <base>/lib, which is on Tomcat's common class path
+pub fn link_shared_libs(project: &ProjectConfig, lib_dir: &Path) -> Result<(), AppError> {
+    for pattern in &project.shared_libs {
+        for jar in find_shared_libs(project, pattern)? {
+            let target = lib_dir.join(jar.file_name().unwrap_or_default());
+            if target.exists() {
+                return Err(AppError::Action(format!(
+                    "shared lib {} conflicts with another lib of the same name (declared in {})",
+                    jar.display(),
+                    project.source.display()
+                )));
+            }
+            log::debug!("Link shared lib: {} -> {}", jar.display(), target.display());
+            link_file(jar.as_path(), target.as_path()).map_err(|e| {
+                AppError::System(format!(
+                    "Failed to link shared lib {}: {}",
+                    jar.display(),
+                    e
+                ))
+            })?;
+        }
+    }
+    Ok(())
+}
+
+fn find_shared_libs(project: &ProjectConfig, pattern: &str) -> Result<Vec<PathBuf>, AppError> {
+    if !pattern.contains(['*', '?', '[']) {
+        if !Path::new(pattern).is_file() {
+            return Err(AppError::Action(format!(
+                "shared lib does not exist: {} (declared in {})",
+                pattern,
+                project.source.display()
+            )));
+        }
+        return Ok(vec![PathBuf::from(pattern)]);
+    }
+
+    let paths = glob::glob(pattern).map_err(|e| {
+        AppError::Action(format!("Invalid shared lib pattern '{}': {}", pattern, e))
+    })?;
+    let mut jars: Vec<PathBuf> = paths.flatten().filter(|t| t.is_file()).collect();
+    if jars.is_empty() {
+        return Err(AppError::Action(format!(
+            "shared lib pattern matches no file: {} (declared in {})",
+            pattern,
+            project.source.display()
+        )));
+    }
+    jars.sort();
+    Ok(jars)
+}
+
+#[cfg(unix)]
+fn link_file(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::os::unix::fs::symlink(source, target)
+}
+
+// creating symlinks needs extra privileges on Windows
+#[cfg(windows)]
+fn link_file(source: &Path, target: &Path) -> std::io::Result<()> {
+    std::fs::copy(source, target).map(|_| ())
+}
+
+// catalina only reads <base>/bin/setenv when it exists and skips the one of CATALINA_HOME,
+// so that one is called afterwards and keeps the last word as before
+pub fn write_setenv(
+    bin_dir: &Path,
+    tomcat_home: &str,
+    variables: &[(String, String)],
+) -> Result<(), AppError> {
+    let home_setenv = Path::new(tomcat_home).join("bin").join(SETENV);
+    let mut exported = Vec::new();
+    for (name, value) in variables {
+        if is_variable_name(name) {
+            exported.push((name.clone(), value.clone()));
+        } else {
+            // still passed to the launched catalina, only the script cannot hold it
+            log::warn!(
+                "Environment variable '{}' is not a valid shell name, left out of {}",
+                name,
+                SETENV
+            );
+        }
+    }
+    let content = render_setenv(exported.as_slice(), home_setenv.is_file())?;
+    let setenv = bin_dir.join(SETENV);
+    log::debug!("Write {}", setenv.display());
+    write_private(setenv.as_path(), content)
+        .map_err(|e| AppError::System(format!("Failed to write {}: {}", setenv.display(), e)))
+}
+
+// [A-Za-z_][A-Za-z0-9_]*
+fn is_variable_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|t| t.is_ascii_alphabetic() || t == '_')
+        && chars.all(|t| t.is_ascii_alphanumeric() || t == '_')
+}
+
+// environment variables may hold secrets read from files
+#[cfg(unix)]
+fn write_private(path: &Path, content: String) -> std::io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::OpenOptionsExt;
+    std::fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(path)?
+        .write_all(content.as_bytes())
+}
+
+#[cfg(windows)]
+fn write_private(path: &Path, content: String) -> std::io::Result<()> {
+    std::fs::write(path, content)
+}
+
+#[cfg(not(windows))]
+fn render_setenv(variables: &[(String, String)], home_setenv: bool) -> Result<String, AppError> {
+    let mut content =
+        String::from("#!/bin/sh\n# Generated by tms, changes are lost on the next run\n");
+    for (name, value) in variables {
+        let value = shlex::try_quote(value).map_err(|e| {
+            AppError::Action(format!(
+                "Invalid value of environment variable '{}': {}",
+                name, e
+            ))
+        })?;
+        content.push_str(format!("{}={}\nexport {}\n", name, value, name).as_str());
+    }
+    if home_setenv {
+        content.push_str(". \"$CATALINA_HOME/bin/setenv.sh\"\n");
+    }
+    Ok(content)
+}
+
+#[cfg(windows)]
+fn render_setenv(variables: &[(String, String)], home_setenv: bool) -> Result<String, AppError> {
+    let mut content =
+        String::from("@echo off\r\nrem Generated by tms, changes are lost on the next run\r\n");
+    for (name, value) in variables {
+        content.push_str(format!("set \"{}={}\"\r\n", name, value.replace('%', "%%")).as_str());
+    }
+    if home_setenv {
+        content.push_str("call \"%CATALINA_HOME%\\bin\\setenv.bat\"\r\n");
+    }
+    Ok(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shell_variable_names() {
+        for name in ["JAVA_OPTS", "_x", "a1"] {
+            assert!(is_variable_name(name), "{}", name);
+        }
+        for name in ["", "1a", "a-b", "a b", "a=b", "a;rm"] {
+            assert!(!is_variable_name(name), "{}", name);
+        }
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use xml_doc::Node;
+
+use crate::app;
+use crate::app::AppError;
+
+const CONF_DIR: &str = "conf";
+const WORK_DIR: &str = "work";
+const LOGS_DIR: &str = "logs";
+const LIB_DIR: &str = "lib";
+const BIN_DIR: &str = "bin";
+// complete CATALINA_BASE layout, so that Tomcat does not have to create any of it
+const LAYOUT: [&str; 7] = [
+    CONF_DIR, LOGS_DIR, "temp", WORK_DIR, "webapps", LIB_DIR, BIN_DIR,
+];
+const STAGING_DIR: &str = ".staging";
+const MANIFEST_FILENAME: &str = "tms-manifest.toml";
+
+// files tms generated into conf, relative to the conf directory
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeSet<String>,
+}
+
+// conf files are generated into a staging directory first and only copied into the
+// project cache when their content changed, so that Tomcat keeps work/ and logs/
+pub struct ProjectCache {
+    root: PathBuf,
+    manifest: Manifest,
+}
+
+impl ProjectCache {
+    pub fn prepare(root: &Path, fresh: bool) -> Result<ProjectCache, AppError> {
+        let manifest_file = root.join(MANIFEST_FILENAME);
+        if fresh || !manifest_file.exists() {
+            if root.exists() {
+                log::info!("Clean project cache: {}", root.display());
+                app::util::remove_dir_items(root)?;
+            } else {
+                log::info!("Create project cache directory: {}", root.display());
+            }
+            app::util::create_dirs(root)?;
+        } else {
+            log::info!("Reuse project cache: {}", root.display());
+            remove_transient_files(root)?;
+        }
+
+        let manifest = match std::fs::read_to_string(manifest_file.as_path()) {
+            Err(_) => Manifest::default(),
+            Ok(content) => toml::from_str(content.as_str()).unwrap_or_else(|e| {
+                log::debug!("Ignore broken cache manifest: {}", e);
+                Manifest::default()
+            }),
+        };
+        let cache = ProjectCache {
+            root: root.to_path_buf(),
+            manifest,
+        };
+        for dir in LAYOUT {
+            app::util::create_dirs(root.join(dir).as_path())?;
+        }
+        app::util::create_dirs(cache.staging_conf().as_path())?;
+        Ok(cache)
+    }
+
+    pub fn conf(&self) -> PathBuf {
+        self.root.join(CONF_DIR)
+    }
+
+    pub fn lib(&self) -> PathBuf {
+        self.root.join(LIB_DIR)
+    }
+
+    pub fn bin(&self) -> PathBuf {
+        self.root.join(BIN_DIR)
+    }
+
+    // where the conf files of this run are generated
+    pub fn staging_conf(&self) -> PathBuf {
+        self.root.join(STAGING_DIR).join(CONF_DIR)
+    }
+
+    // the path a staged file will have once committed
+    pub fn committed_path(&self, staged: &Path) -> PathBuf {
+        match staged.strip_prefix(self.staging_conf()) {
+            Ok(relative) => self.conf().join(relative),
+            Err(_) => staged.to_path_buf(),
+        }
+    }
+
+    pub fn commit(mut self) -> Result<(), AppError> {
+        let staging_conf = self.staging_conf();
+        let conf = self.conf();
+        let mut files = BTreeSet::new();
+        for staged in list_files(staging_conf.as_path())? {
+            let relative = staged
+                .strip_prefix(staging_conf.as_path())
+                .unwrap()
+                .to_string_lossy()
+                .replace('\\', "/");
+            let content = std::fs::read(staged.as_path()).map_err(|e| {
+                AppError::System(format!("Failed to read {}: {}", staged.display(), e))
+            })?;
+            let target = conf.join(relative.as_str());
+            // a file missing from the manifest is rewritten even when its content matches
+            let unchanged = self.manifest.files.contains(&relative)
+                && std::fs::read(target.as_path())
+                    .is_ok_and(|t| t == content || same_xml(relative.as_str(), &t, &content));
+            if unchanged {
+                log::debug!("Keep cache file: conf/{}", relative);
+            } else {
+                log::debug!("Update cache file: conf/{}", relative);
+                if let Some(parent) = target.parent() {
+                    app::util::create_dirs(parent)?;
+                }
+                std::fs::write(target.as_path(), content).map_err(|e| {
+                    AppError::System(format!("Failed to write {}: {}", target.display(), e))
+                })?;
+            }
+            files.insert(relative);
+        }
+
+        for relative in &self.manifest.files {
+            if !files.contains(relative) {
+                self.remove_stale(relative)?;
+            }
+        }
+
+        self.manifest.files = files;
+        let content = toml::to_string(&self.manifest)
+            .map_err(|e| AppError::System(format!("Failed to serialize cache manifest: {}", e)))?;
+        std::fs::write(self.root.join(MANIFEST_FILENAME), content)
+            .map_err(|e| AppError::System(format!("Failed to write cache manifest: {}", e)))?;
+        app::util::remove_dir_items(self.root.join(STAGING_DIR).as_path())
+    }
+
+    // context descriptors of items that no longer run take their compiled JSPs with them
+    fn remove_stale(&self, relative: &str) -> Result<(), AppError> {
+        log::debug!("Remove stale cache file: conf/{}", relative);
+        let target = self.conf().join(relative);
+        if target.exists() {
+            std::fs::remove_file(target.as_path()).map_err(|e| {
+                AppError::System(format!("Failed to remove {}: {}", target.display(), e))
+            })?;
+        }
+        if let Some(descriptor) = relative.strip_suffix(".xml") {
+            if descriptor.matches('/').count() == 2 {
+                app::util::remove_dir_items(self.root.join(WORK_DIR).join(descriptor).as_path())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+// everything except conf, work, logs and the manifest is recreated on each run
+fn remove_transient_files(root: &Path) -> Result<(), AppError> {
+    let entries = std::fs::read_dir(root)
+        .map_err(|e| AppError::System(format!("Failed to read {}: {}", root.display(), e)))?;
+    for path in entries.flatten().map(|t| t.path()) {
+        let name = path.file_name().unwrap_or_default().to_string_lossy();
+        if [CONF_DIR, WORK_DIR, LOGS_DIR, MANIFEST_FILENAME].contains(&name.as_ref()) {
+            continue;
+        }
+        let result = if path.is_dir() {
+            std::fs::remove_dir_all(path.as_path())
+        } else {
+            std::fs::remove_file(path.as_path())
+        };
+        result
+            .map_err(|e| AppError::System(format!("Failed to remove {}: {}", path.display(), e)))?;
+    }
+    Ok(())
+}
+
+// xml_doc keeps attributes in a HashMap, so the same document is written with its
+// attributes in another order on each run
+fn same_xml(relative: &str, a: &[u8], b: &[u8]) -> bool {
+    if !relative.ends_with(".xml") {
+        return false;
+    }
+    match (
+        xml_doc::Document::parse_reader(a),
+        xml_doc::Document::parse_reader(b),
+    ) {
+        (Ok(a_doc), Ok(b_doc)) => {
+            same_nodes((&a_doc, a_doc.root_nodes()), (&b_doc, b_doc.root_nodes()))
+        }
+        _ => false,
+    }
+}
+
+fn same_nodes(a: (&xml_doc::Document, &[Node]), b: (&xml_doc::Document, &[Node])) -> bool {
+    let ((a_doc, a_nodes), (b_doc, b_nodes)) = (a, b);
+    a_nodes.len() == b_nodes.len()
+        && a_nodes.iter().zip(b_nodes).all(|pair| match pair {
+            (Node::Element(x), Node::Element(y)) => {
+                x.full_name(a_doc) == y.full_name(b_doc)
+                    && x.attributes(a_doc) == y.attributes(b_doc)
+                    && x.namespace_decls(a_doc) == y.namespace_decls(b_doc)
+                    && same_nodes((a_doc, x.children(a_doc)), (b_doc, y.children(b_doc)))
+            }
+            (Node::Text(x), Node::Text(y))
+            | (Node::Comment(x), Node::Comment(y))
+            | (Node::CData(x), Node::CData(y))
+            | (Node::PI(x), Node::PI(y))
+            | (Node::DocType(x), Node::DocType(y)) => x == y,
+            _ => false,
+        })
+}
+
+fn list_files(dir: &Path) -> Result<Vec<PathBuf>, AppError> {
+    let mut files = Vec::new();
+    let entries = std::fs::read_dir(dir)
+        .map_err(|e| AppError::System(format!("Failed to read {}: {}", dir.display(), e)))?;
+    for path in entries.flatten().map(|t| t.path()) {
+        if path.is_dir() {
+            files.extend(list_files(path.as_path())?);
+        } else {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("tms-cache-{}-{}", std::process::id(), name));
+        let _ = std::fs::remove_dir_all(root.as_path());
+        root
+    }
+
+    // stages the files of a run and commits them, returns the conf directory
+    fn commit(root: &Path, files: &[(&str, &str)]) -> PathBuf {
+        let cache = ProjectCache::prepare(root, false).unwrap();
+        for (relative, content) in files {
+            let staged = cache.staging_conf().join(relative);
+            std::fs::create_dir_all(staged.parent().unwrap()).unwrap();
+            std::fs::write(staged, content).unwrap();
+        }
+        let conf = cache.conf();
+        cache.commit().unwrap();
+        conf
+    }
+
+    // an old modification time tells whether a file was rewritten
+    fn age(path: &Path) -> SystemTime {
+        let old = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let file = std::fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(old).unwrap();
+        old
+    }
+
+    fn modified(path: &Path) -> SystemTime {
+        std::fs::metadata(path).unwrap().modified().unwrap()
+    }
+
+    #[test]
+    fn keep_unchanged_file() {
+        let root = temp_root("unchanged");
+        let conf = commit(&root, &[("server.xml", "a")]);
+        let old = age(conf.join("server.xml").as_path());
+        commit(&root, &[("server.xml", "a")]);
+        assert_eq!(modified(conf.join("server.xml").as_path()), old);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn keep_reordered_attributes() {
+        let root = temp_root("reordered");
+        let server = |attributes| format!("<?xml version=\"1.0\"?>\n<Server {}/>", attributes);
+        let conf = commit(
+            &root,
+            &[("server.xml", &server(r#"port="8005" shutdown="X""#))],
+        );
+        let old = age(conf.join("server.xml").as_path());
+        commit(
+            &root,
+            &[("server.xml", &server(r#"shutdown="X" port="8005""#))],
+        );
+        assert_eq!(modified(conf.join("server.xml").as_path()), old);
+        commit(
+            &root,
+            &[("server.xml", &server(r#"shutdown="X" port="8006""#))],
+        );
+        assert_ne!(modified(conf.join("server.xml").as_path()), old);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rewrite_changed_file() {
+        let root = temp_root("changed");
+        let conf = commit(&root, &[("server.xml", "a")]);
+        commit(&root, &[("server.xml", "b")]);
+        let content = std::fs::read_to_string(conf.join("server.xml")).unwrap();
+        assert_eq!(content, "b");
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn remove_dropped_descriptor() {
+        let root = temp_root("dropped");
+        let descriptor = "Catalina/localhost/app.xml";
+        let conf = commit(&root, &[("server.xml", "a"), (descriptor, "c")]);
+        let work = root.join(WORK_DIR).join("Catalina/localhost/app");
+        std::fs::create_dir_all(work.as_path()).unwrap();
+        std::fs::write(work.join("index_jsp.class"), "x").unwrap();
+        commit(&root, &[("server.xml", "a")]);
+        assert!(!conf.join(descriptor).exists());
+        assert!(!work.exists());
+        assert!(conf.join("server.xml").exists());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rewrite_all_with_broken_manifest() {
+        let root = temp_root("broken");
+        let conf = commit(&root, &[("server.xml", "a")]);
+        let old = age(conf.join("server.xml").as_path());
+        std::fs::write(root.join(MANIFEST_FILENAME), "files = 1").unwrap();
+        commit(&root, &[("server.xml", "a")]);
+        assert_ne!(modified(conf.join("server.xml").as_path()), old);
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
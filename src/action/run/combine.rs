@@ -1,7 +1,9 @@
-use crate::config::RuntimeConfig;
+use crate::config::{ProjectConfig, ProjectEnvConfig, RuntimeConfig, TmsConfig, VariableConfig};
 use crate::app::util::check_port;
 use crate::app::AppError;
 use crate::app;
+use crate::app::version::VersionReq;
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 
 pub struct RuntimeConfigCombine<'a> {
@@ -13,20 +15,52 @@ impl<'a> RuntimeConfigCombine<'a> {
         Self { configs }
     }
 
-    pub fn java_home(&self) -> Result<&String, AppError> {
-        let mut value = None;
+    // layers from high to low: profile given on the command line, project env overlay,
+    // project inline runtime, project runtime profile, default
+    pub fn for_project(
+        config: &'a TmsConfig,
+        project: &'a ProjectConfig,
+        env: Option<&'a ProjectEnvConfig>,
+        profile: Option<&str>,
+    ) -> Result<RuntimeConfigCombine<'a>, AppError> {
+        let get_profile = |name: &str| {
+            config.runtimes.get(name).ok_or_else(|| {
+                AppError::Argument(format!("Runtime profile '{}' is not defined", name))
+            })
+        };
+        let mut runtimes = Vec::new();
+        if let Some(profile) = profile {
+            runtimes.push(get_profile(profile)?);
+        }
+        if let Some(runtime) = env.and_then(|t| t.runtime.as_ref()) {
+            runtimes.push(runtime);
+        }
+        if let Some(runtime) = &project.runtime {
+            runtimes.push(runtime);
+        }
+        if let Some(profile) = &project.runtime_profile {
+            runtimes.push(get_profile(profile)?);
+        }
+        runtimes.push(&config.default);
+        Ok(Self::new(runtimes))
+    }
+
+    // the highest layer setting java_home or java_version decides, without either
+    // the newest discovered JDK is used
+    pub fn java_home(&self) -> Result<String, AppError> {
+        let mut java_home = None;
+        let mut java_version = None;
         for config in &self.configs {
-            if config.java.is_none() {
-                continue;
-            }
-            let java = config.java.as_ref().unwrap();
-            if java.java_home.is_some() {
-                value = java.java_home.as_ref();
-                break;
+            if let Some(java) = config.java.as_ref() {
+                if java.java_home.is_some() || java.java_version.is_some() {
+                    java_home = java.java_home.as_ref();
+                    java_version = java.java_version.as_ref();
+                    break;
+                }
             }
         }
-        match value {
-            None => Err(AppError::Action("java_home is not specified".to_string())),
+        match java_home {
+            None => find_jdk(java_version),
             Some(value) => {
                 let java_home: PathBuf = value.into();
                 if !java_home.exists() {
@@ -42,44 +76,47 @@ impl<'a> RuntimeConfigCombine<'a> {
                         java_home.display()
                     )));
                 }
-                Ok(value)
+                Ok(value.clone())
             }
         }
     }
 
-    pub fn java_options(&self) -> &'a str {
-        let mut value = None;
-        for config in &self.configs {
+    pub fn java_options(&self) -> Vec<String> {
+        let mut options = Vec::new();
+        // start from the lowest priority config, each layer replaces or extends the options
+        for config in self.configs.iter().rev() {
             if config.java.is_none() {
                 continue;
             }
             let java = config.java.as_ref().unwrap();
-            if java.java_options.is_some() {
-                value = java.java_options.as_ref();
-                break;
+            if let Some(java_options) = &java.java_options {
+                options.clone_from(java_options);
             }
+            let mut layered = java.java_options_prepend.clone();
+            layered.append(&mut options);
+            layered.extend(java.java_options_append.iter().cloned());
+            options = layered;
         }
-        match value {
-            None => "",
-            Some(value) => value,
-        }
+        options
     }
 
-    pub fn tomcat_home(&self) -> Result<&String, AppError> {
-        let mut value = None;
+    // the highest layer setting tomcat_home or tomcat_version decides
+    pub fn tomcat_home(&self) -> Result<String, AppError> {
+        let mut tomcat_home = None;
+        let mut tomcat_version = None;
         for config in &self.configs {
-            if config.tomcat.is_none() {
-                continue;
-            }
-            let tomcat = config.tomcat.as_ref().unwrap();
-            if tomcat.tomcat_home.is_some() {
-                value = tomcat.tomcat_home.as_ref();
-                break;
+            if let Some(tomcat) = config.tomcat.as_ref() {
+                if tomcat.tomcat_home.is_some() || tomcat.tomcat_version.is_some() {
+                    tomcat_home = tomcat.tomcat_home.as_ref();
+                    tomcat_version = tomcat.tomcat_version.as_ref();
+                    break;
+                }
             }
         }
-        match value {
-            None => Err(AppError::Action("tomcat_home is not specified".to_string())),
-            Some(value) => {
+        match (tomcat_home, tomcat_version) {
+            (None, None) => Err(AppError::Action("tomcat_home is not specified".to_string())),
+            (None, Some(version)) => find_tomcat(version),
+            (Some(value), _) => {
                 let tomcat_home: PathBuf = value.into();
                 if !tomcat_home.exists() {
                     return Err(AppError::Action(format!(
@@ -94,7 +131,7 @@ impl<'a> RuntimeConfigCombine<'a> {
                         tomcat_home.display()
                     )));
                 }
-                Ok(value)
+                Ok(value.clone())
             }
         }
     }
@@ -154,4 +191,132 @@ impl<'a> RuntimeConfigCombine<'a> {
             Some(value) => check_port("jpda_port", value),
         }
     }
+
+    // HTTPS is only enabled when a layer sets https_port
+    pub fn https_port(&self) -> Result<Option<u32>, AppError> {
+        let value = self
+            .configs
+            .iter()
+            .filter_map(|t| t.tomcat.as_ref())
+            .find_map(|t| t.https_port);
+        value.map(|t| check_port("https_port", t)).transpose()
+    }
+
+    // keystore file and password of the highest layer setting https_keystore
+    pub fn https_keystore(&self) -> Option<(String, Option<String>)> {
+        self.configs
+            .iter()
+            .filter_map(|t| t.tomcat.as_ref())
+            .find(|t| t.https_keystore.is_some())
+            .map(|t| {
+                (
+                    t.https_keystore.clone().unwrap(),
+                    t.https_keystore_password.clone(),
+                )
+            })
+    }
+
+    pub fn shutdown_timeout(&self) -> Result<u64, AppError> {
+        let mut value = None;
+        for config in &self.configs {
+            if config.tomcat.is_none() {
+                continue;
+            }
+            let tomcat = config.tomcat.as_ref().unwrap();
+            if tomcat.shutdown_timeout.is_some() {
+                value.clone_from(&tomcat.shutdown_timeout);
+                break;
+            }
+        }
+
+        match value {
+            None => Err(AppError::Action(
+                "shutdown_timeout is not specified".to_string(),
+            )),
+            Some(value) => Ok(value),
+        }
+    }
+
+    pub fn env(&self) -> Result<BTreeMap<String, String>, AppError> {
+        self.merge_variables(|config| &config.env)
+    }
+
+    pub fn system_properties(&self) -> Result<BTreeMap<String, String>, AppError> {
+        self.merge_variables(|config| &config.system_properties)
+    }
+
+    fn merge_variables<F>(&self, select: F) -> Result<BTreeMap<String, String>, AppError>
+    where
+        F: Fn(&RuntimeConfig) -> &BTreeMap<String, VariableConfig>,
+    {
+        let mut values = BTreeMap::new();
+        // later configs have lower priority, so apply them first
+        for config in self.configs.iter().rev() {
+            for (name, variable) in select(config) {
+                values.insert(name.clone(), resolve_variable(name, variable)?);
+            }
+        }
+        Ok(values)
+    }
+}
+
+pub fn resolve_variable(name: &str, variable: &VariableConfig) -> Result<String, AppError> {
+    match variable {
+        VariableConfig::Value(value) => Ok(value.clone()),
+        VariableConfig::File(file) => match std::fs::read_to_string(file) {
+            Ok(value) => Ok(value.trim_end_matches(['\r', '\n']).to_string()),
+            Err(e) => Err(AppError::Action(format!(
+                "Failed to read value of '{}' from file '{}': {}",
+                name, file, e
+            ))),
+        },
+    }
+}
+
+fn find_jdk(java_version: Option<&String>) -> Result<String, AppError> {
+    let requirement = match java_version {
+        None => None,
+        Some(version) => Some(VersionReq::parse(version).map_err(AppError::Config)?),
+    };
+    let jdks = app::jdk::discover();
+    let jdk = jdks
+        .iter()
+        .find(|jdk| requirement.as_ref().is_none_or(|t| t.matches(&jdk.version)));
+    match (jdk, java_version) {
+        (Some(jdk), _) => {
+            log::info!("Using JDK {} at {}", jdk.version, jdk.home.display());
+            Ok(jdk.home.display().to_string())
+        }
+        (None, None) => Err(AppError::Action(
+            "java_home is not specified and no JDK was found".to_string(),
+        )),
+        (None, Some(version)) => {
+            let found: Vec<&str> = jdks.iter().map(|t| t.version.as_str()).collect();
+            Err(AppError::Action(format!(
+                "No JDK matches java_version '{}', found: [{}]",
+                version,
+                found.join(", ")
+            )))
+        }
+    }
+}
+
+fn find_tomcat(tomcat_version: &str) -> Result<String, AppError> {
+    let requirement = VersionReq::parse(tomcat_version).map_err(AppError::Config)?;
+    let tomcats = app::tomcat::installed();
+    match tomcats.iter().find(|t| requirement.matches(&t.version)) {
+        Some(tomcat) => {
+            log::info!("Using Tomcat {} at {}", tomcat.version, tomcat.home.display());
+            Ok(tomcat.home.display().to_string())
+        }
+        None => {
+            let found: Vec<&str> = tomcats.iter().map(|t| t.version.as_str()).collect();
+            Err(AppError::Action(format!(
+                "No installed Tomcat matches tomcat_version '{}', found: [{}], \
+                 install one with `tms tomcat add <archive>`",
+                tomcat_version,
+                found.join(", ")
+            )))
+        }
+    }
 }
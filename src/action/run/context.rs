@@ -0,0 +1,174 @@
+use std::path::{Path, PathBuf};
+
+use crate::app::AppError;
+use crate::config::{ProjectItemConfig, ResourceSetConfig};
+
+const ROOT_CONTEXT: &str = "ROOT";
+
+pub fn descriptor_name(context_path: &str) -> String {
+    let name = context_path.trim_matches('/');
+    if name.is_empty() {
+        ROOT_CONTEXT.to_string()
+    } else {
+        name.replace('/', "#")
+    }
+}
+
+pub fn descriptor_path(host_conf: &Path, context_path: &str) -> PathBuf {
+    host_conf.join(format!("{}.xml", descriptor_name(context_path)))
+}
+
+pub fn write_item_descriptors(
+    items: &[ProjectItemConfig],
+    host_conf: &Path,
+) -> Result<(), AppError> {
+    for item in items {
+        let mut doc = xml_doc::Document::new();
+        let context_element = build_item_context(&mut doc, item)?;
+        write_descriptor(host_conf, item.context_path.as_str(), doc, context_element)?;
+    }
+    Ok(())
+}
+
+fn build_item_context(
+    doc: &mut xml_doc::Document,
+    item: &ProjectItemConfig,
+) -> Result<xml_doc::Element, AppError> {
+    let context = &item.context;
+    let context_element = xml_doc::Element::build(doc, "Context")
+        .attribute("docBase", item.path.to_string())
+        .attribute("reloadable", context.reloadable.unwrap_or(true).to_string())
+        .finish();
+    if let Some(cross_context) = context.cross_context {
+        context_element.set_attribute(doc, "crossContext", cross_context.to_string());
+    }
+    if let Some(cookies) = context.cookies {
+        context_element.set_attribute(doc, "cookies", cookies.to_string());
+    }
+
+    let mut children = Vec::new();
+    if let Some(web_resources) = &context.web_resources {
+        let resources_element = xml_doc::Element::build(doc, "Resources").finish();
+        if let Some(caching_allowed) = web_resources.caching_allowed {
+            resources_element.set_attribute(doc, "cachingAllowed", caching_allowed.to_string());
+        }
+        if let Some(cache_max_size) = web_resources.cache_max_size {
+            resources_element.set_attribute(doc, "cacheMaxSize", cache_max_size.to_string());
+        }
+        if let Some(cache_ttl) = web_resources.cache_ttl {
+            resources_element.set_attribute(doc, "cacheTtl", cache_ttl.to_string());
+        }
+        for (tag, resource_sets) in [
+            ("PreResources", &web_resources.pre),
+            ("JarResources", &web_resources.jar),
+            ("PostResources", &web_resources.post),
+        ] {
+            for resource_set in resource_sets {
+                let element = build_resource_set(doc, tag, resource_set);
+                push_child(doc, resources_element, element)?;
+            }
+        }
+        children.push(resources_element);
+    }
+
+    for resource in &context.resources {
+        let element = xml_doc::Element::build(doc, "Resource").finish();
+        for (key, value) in resource {
+            element.set_attribute(doc, key.as_str(), value.as_str());
+        }
+        children.push(element);
+    }
+
+    for environment in &context.environments {
+        let element = xml_doc::Element::build(doc, "Environment")
+            .attribute("name", environment.name.as_str())
+            .attribute("value", environment.value.as_str())
+            .attribute("type", environment.value_type.as_str())
+            .finish();
+        if let Some(override_value) = environment.override_value {
+            element.set_attribute(doc, "override", override_value.to_string());
+        }
+        children.push(element);
+    }
+
+    for parameter in &context.parameters {
+        let element = xml_doc::Element::build(doc, "Parameter")
+            .attribute("name", parameter.name.as_str())
+            .attribute("value", parameter.value.as_str())
+            .finish();
+        if let Some(override_value) = parameter.override_value {
+            element.set_attribute(doc, "override", override_value.to_string());
+        }
+        children.push(element);
+    }
+
+    for child in children {
+        push_child(doc, context_element, child)?;
+    }
+    Ok(context_element)
+}
+
+fn build_resource_set(
+    doc: &mut xml_doc::Document,
+    tag: &str,
+    resource_set: &ResourceSetConfig,
+) -> xml_doc::Element {
+    let element = xml_doc::Element::build(doc, tag)
+        .attribute("className", resource_set.class_name.as_str())
+        .attribute("base", resource_set.base.as_str())
+        .finish();
+    if let Some(web_app_mount) = &resource_set.web_app_mount {
+        element.set_attribute(doc, "webAppMount", web_app_mount.as_str());
+    }
+    if let Some(internal_path) = &resource_set.internal_path {
+        element.set_attribute(doc, "internalPath", internal_path.as_str());
+    }
+    if let Some(read_only) = resource_set.read_only {
+        element.set_attribute(doc, "readOnly", read_only.to_string());
+    }
+    element
+}
+
+fn push_child(
+    doc: &mut xml_doc::Document,
+    parent: xml_doc::Element,
+    child: xml_doc::Element,
+) -> Result<(), AppError> {
+    parent
+        .push_child(doc, xml_doc::Node::Element(child))
+        .map_err(|e| AppError::Action(format!("Failed to build context descriptor: {:?}", e)))
+}
+
+pub fn write_descriptor(
+    host_conf: &Path,
+    context_path: &str,
+    mut doc: xml_doc::Document,
+    context_element: xml_doc::Element,
+) -> Result<PathBuf, AppError> {
+    let descriptor = descriptor_path(host_conf, context_path);
+    if descriptor.exists() {
+        return Err(AppError::Action(format!(
+            "Context path '{}' is used more than once",
+            context_path
+        )));
+    }
+
+    doc.push_root_node(xml_doc::Node::Element(context_element))
+        .map_err(|e| AppError::Action(format!("Failed to build context descriptor: {:?}", e)))?;
+    let content = doc
+        .write_str()
+        .map_err(|e| AppError::Action(format!("Failed to build context descriptor: {:?}", e)))?;
+    log::debug!(
+        "Write context descriptor: {} -> {}",
+        context_path,
+        descriptor.display()
+    );
+    std::fs::write(descriptor.as_path(), content).map_err(|e| {
+        AppError::Action(format!(
+            "Failed to write context descriptor {}: {}",
+            descriptor.display(),
+            e
+        ))
+    })?;
+    Ok(descriptor)
+}
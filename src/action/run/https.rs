@@ -0,0 +1,116 @@
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::app;
+use crate::app::AppError;
+use crate::config;
+
+const KEYSTORE_FILENAME: &str = "tms-localhost.p12";
+const KEYSTORE_PASSWORD: &str = "changeit";
+const KEYSTORE_ALIAS: &str = "tms";
+const HTTPS_PROTOCOL: &str = "org.apache.coyote.http11.Http11NioProtocol";
+
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    pub file: String,
+    pub password: String,
+}
+
+// a configured keystore, or the self-signed one shared by all projects so that
+// the browser only has to trust it once
+pub fn keystore(
+    configured: Option<(String, Option<String>)>,
+    java_home: &str,
+) -> Result<Keystore, AppError> {
+    if let Some((file, password)) = configured {
+        if !Path::new(&file).is_file() {
+            return Err(AppError::Action(format!(
+                "https_keystore does not exist: {}",
+                file
+            )));
+        }
+        return Ok(Keystore {
+            file,
+            password: password.unwrap_or_else(|| KEYSTORE_PASSWORD.to_string()),
+        });
+    }
+
+    let file = config::get_cache_dir().join(KEYSTORE_FILENAME);
+    if !file.exists() {
+        generate_keystore(file.as_path(), java_home)?;
+    }
+    Ok(Keystore {
+        file: file.display().to_string(),
+        password: KEYSTORE_PASSWORD.to_string(),
+    })
+}
+
+fn generate_keystore(file: &Path, java_home: &str) -> Result<(), AppError> {
+    if let Some(parent) = file.parent() {
+        app::util::create_dirs(parent)?;
+    }
+    let keytool = PathBuf::from(java_home).join(app::constant::KEYTOOL_BIN);
+    log::info!("Generate self-signed certificate: {}", file.display());
+    let output = Command::new(keytool.as_path())
+        .args(["-genkeypair", "-alias", KEYSTORE_ALIAS])
+        .args(["-keyalg", "RSA", "-keysize", "2048", "-validity", "3650"])
+        .args(["-dname", "CN=localhost"])
+        .args(["-ext", "SAN=dns:localhost,ip:127.0.0.1,ip:::1"])
+        .args(["-storetype", "PKCS12", "-keystore"])
+        .arg(file)
+        .args(["-storepass", KEYSTORE_PASSWORD])
+        .args(["-keypass", KEYSTORE_PASSWORD])
+        .output()
+        .map_err(|e| AppError::System(format!("Failed to run {}: {}", keytool.display(), e)))?;
+    if !output.status.success() {
+        // keytool may leave a broken keystore behind
+        let _ = std::fs::remove_file(file);
+        return Err(AppError::Action(format!(
+            "Failed to generate keystore with {}: {}",
+            keytool.display(),
+            // keytool reports most errors on stdout
+            String::from_utf8_lossy(&[output.stdout, output.stderr].concat()).trim()
+        )));
+    }
+    Ok(())
+}
+
+// inserted in front of the Engine, as Tomcat's own server.xml declares its connectors
+pub fn add_https_connector(
+    doc: &mut xml_doc::Document,
+    service: xml_doc::Element,
+    https_port: u32,
+    keystore: &Keystore,
+) -> Result<(), AppError> {
+    let connector = xml_doc::Element::build(doc, "Connector")
+        .attribute("port", https_port.to_string())
+        .attribute("protocol", HTTPS_PROTOCOL)
+        .attribute("SSLEnabled", "true")
+        .attribute("scheme", "https")
+        .attribute("secure", "true")
+        .finish();
+    let host_config = xml_doc::Element::build(doc, "SSLHostConfig").finish();
+    let certificate = xml_doc::Element::build(doc, "Certificate")
+        .attribute("certificateKeystoreFile", keystore.file.as_str())
+        .attribute("certificateKeystorePassword", keystore.password.as_str())
+        .attribute("certificateKeystoreType", "PKCS12")
+        .attribute("type", "RSA")
+        .finish();
+    let error = |e| AppError::Action(format!("Failed to add HTTPS connector: {:?}", e));
+    host_config
+        .push_child(doc, xml_doc::Node::Element(certificate))
+        .map_err(error)?;
+    connector
+        .push_child(doc, xml_doc::Node::Element(host_config))
+        .map_err(error)?;
+
+    let engine_index = service.children(doc).iter().position(|t| match t {
+        xml_doc::Node::Element(element) => element.name(doc) == "Engine",
+        _ => false,
+    });
+    match engine_index {
+        Some(index) => service.insert_child(doc, index, xml_doc::Node::Element(connector)),
+        None => service.push_child(doc, xml_doc::Node::Element(connector)),
+    }
+    .map_err(error)
+}
@@ -0,0 +1,144 @@
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::action::run::context;
+use crate::app;
+use crate::app::AppError;
+
+const MANAGER_PATH: &str = "/manager";
+const MANAGER_ROLE: &str = "manager-script";
+const MANAGER_USER: &str = "tms";
+const MANAGER_ALLOW: &str = r"127\.\d+\.\d+\.\d+|::1|0:0:0:0:0:0:0:1";
+const TOMCAT_USERS_XML: &str = "tomcat-users.xml";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct Manager {
+    http_port: u32,
+    username: String,
+    password: String,
+}
+
+impl Manager {
+    pub fn install(
+        tomcat_home: &str,
+        cache_conf: &Path,
+        host_conf: &Path,
+        http_port: u32,
+    ) -> Result<Option<Manager>, AppError> {
+        let manager_app = PathBuf::from(tomcat_home).join("webapps").join("manager");
+        if !manager_app.exists() {
+            log::info!(
+                "Manager application not found: {}, reloading by touching context descriptors",
+                manager_app.display()
+            );
+            return Ok(None);
+        }
+        if context::descriptor_path(host_conf, MANAGER_PATH).exists() {
+            log::info!(
+                "Context path '{}' is used by an item, reloading by touching context descriptors",
+                MANAGER_PATH
+            );
+            return Ok(None);
+        }
+
+        let manager = Manager {
+            http_port,
+            username: MANAGER_USER.to_string(),
+            password: random_password()?,
+        };
+        add_manager_context(host_conf, manager_app.as_path())?;
+        add_manager_user(cache_conf, &manager)?;
+        Ok(Some(manager))
+    }
+
+    pub fn reload(&self, context_path: &str) -> Result<(), AppError> {
+        let request = format!(
+            "GET {}/text/reload?path={} HTTP/1.0\r\nHost: localhost:{}\r\nAuthorization: Basic {}\r\n\r\n",
+            MANAGER_PATH,
+            app::util::url_encode(context_path),
+            self.http_port,
+            app::util::base64_encode(format!("{}:{}", self.username, self.password).as_bytes())
+        );
+
+        let address = SocketAddr::from((Ipv4Addr::LOCALHOST, self.http_port as u16));
+        let mut response = String::new();
+        TcpStream::connect_timeout(&address, REQUEST_TIMEOUT)
+            .and_then(|mut stream| {
+                stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+                stream.write_all(request.as_bytes())?;
+                stream.read_to_string(&mut response)
+            })
+            .map_err(|e| AppError::Action(format!("Failed to call tomcat manager: {}", e)))?;
+
+        let body = response.split("\r\n\r\n").nth(1).unwrap_or("").trim();
+        if body.starts_with("OK") {
+            Ok(())
+        } else {
+            let status = response.lines().next().unwrap_or("");
+            Err(AppError::Action(format!(
+                "Failed to reload context '{}': {} {}",
+                context_path, status, body
+            )))
+        }
+    }
+}
+
+// the manager-script user can deploy code, so its password comes from the OS random source
+fn random_password() -> Result<String, AppError> {
+    let mut bytes = [0u8; 16];
+    getrandom::getrandom(&mut bytes)
+        .map_err(|e| AppError::System(format!("Failed to generate manager password: {}", e)))?;
+    Ok(bytes.iter().map(|t| format!("{:02x}", t)).collect())
+}
+
+// a descriptor replaces the META-INF/context.xml of the manager, so its localhost-only
+// valve has to be repeated here
+fn add_manager_context(host_conf: &Path, manager_app: &Path) -> Result<(), AppError> {
+    let mut doc = xml_doc::Document::new();
+    let context_element = xml_doc::Element::build(&mut doc, "Context")
+        .attribute("docBase", manager_app.display().to_string())
+        .attribute("privileged", "true")
+        .finish();
+    let valve_element = xml_doc::Element::build(&mut doc, "Valve")
+        .attribute("className", "org.apache.catalina.valves.RemoteAddrValve")
+        .attribute("allow", MANAGER_ALLOW)
+        .finish();
+    context_element
+        .push_child(&mut doc, xml_doc::Node::Element(valve_element))
+        .map_err(|e| AppError::Action(format!("Failed to build manager context: {:?}", e)))?;
+    context::write_descriptor(host_conf, MANAGER_PATH, doc, context_element)?;
+    Ok(())
+}
+
+fn add_manager_user(cache_conf: &Path, manager: &Manager) -> Result<(), AppError> {
+    let cache_users_xml = cache_conf.join(TOMCAT_USERS_XML);
+    let mut doc = xml_doc::Document::parse_file(cache_users_xml.as_path())
+        .map_err(|e| AppError::Action(format!("Failed to parse tomcat-users.xml: {:?}", e)))?;
+    let root_element = doc.root_element().unwrap();
+    let role_element = xml_doc::Element::build(&mut doc, "role")
+        .attribute("rolename", MANAGER_ROLE)
+        .finish();
+    let user_element = xml_doc::Element::build(&mut doc, "user")
+        .attribute("username", manager.username.as_str())
+        .attribute("password", manager.password.as_str())
+        .attribute("roles", MANAGER_ROLE)
+        .finish();
+    for element in [role_element, user_element] {
+        root_element
+            .push_child(&mut doc, xml_doc::Node::Element(element))
+            .map_err(|e| {
+                AppError::Action(format!(
+                    "Failed to add manager user to tomcat-users.xml: {:?}",
+                    e
+                ))
+            })?;
+    }
+
+    let new_users_xml = doc
+        .write_str()
+        .map_err(|e| AppError::Action(format!("Failed to write tomcat-users.xml: {:?}", e)))?;
+    std::fs::write(cache_users_xml.as_path(), new_users_xml)
+        .map_err(|e| AppError::Action(format!("Failed to write tomcat-users.xml: {:?}", e)))
+}
@@ -1,24 +1,172 @@
 use crate::action::run::combine::RuntimeConfigCombine;
+use crate::action::state::ProjectState;
+use std::collections::BTreeMap;
 use std::path::{Path, PathBuf};
-use std::process::Stdio;
+use std::process::{Child, Command, Stdio};
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
 
 use crate::app;
 use crate::config;
-use crate::config::{ProjectItemConfig, TmsConfig};
+use crate::config::TmsConfig;
 use crate::app::util::check_port;
 use crate::app::AppError;
 use crate::app::arg::ActionRun;
 
-mod combine;
+mod base;
+mod cache;
+pub mod combine;
+mod context;
+mod https;
+mod manager;
+mod port;
+mod template;
+mod watch;
+mod webxml;
 
-pub fn run_project(action: &ActionRun, config: &TmsConfig) -> Result<(), AppError> {
-    let project = super::get_project(config, action.project.as_str())?;
+const CONSOLE_LOG: &str = "console.log";
+const CONTEXT_XML: &str = "context.xml";
+const DEFAULT_HOST: &str = "localhost";
+const RESERVED_ENV: [&str; 6] = [
+    app::constant::JAVA_HOME,
+    app::constant::CATALINA_HOME,
+    app::constant::CATALINA_BASE,
+    app::constant::CATALINA_OPTS,
+    app::constant::JPDA_ADDRESS,
+    app::constant::TITLE,
+];
+
+struct ServerPorts {
+    http_port: u32,
+    server_port: u32,
+    https: Option<(u32, https::Keystore)>,
+}
+
+struct Launch {
+    command: Command,
+    project_name: String,
+    project_cache: PathBuf,
+    items: Vec<String>,
+    http_port: u32,
+    server_port: u32,
+    jpda_port: Option<u32>,
+    https_port: Option<u32>,
+    shutdown_timeout: u64,
+    watcher: Option<watch::ItemWatcher>,
+}
+
+impl Launch {
+    fn state(&self, pid: u32, detached: bool) -> ProjectState {
+        ProjectState {
+            project: self.project_name.clone(),
+            pid,
+            detached,
+            http_port: self.http_port,
+            server_port: self.server_port,
+            jpda_port: self.jpda_port,
+            https_port: self.https_port,
+            shutdown_timeout: self.shutdown_timeout,
+            items: self.items.clone(),
+            started_at: crate::action::state::now(),
+        }
+    }
+
+    fn log_started(&self, pid: u32) {
+        match self.jpda_port {
+            Some(jpda_port) => log::info!(
+                "Starting Tomcat > PID: {}, HTTP port: {}, JPDA port: {}",
+                pid,
+                self.http_port,
+                jpda_port
+            ),
+            None => log::info!(
+                "Starting Tomcat > PID: {}, HTTP port: {}",
+                pid,
+                self.http_port
+            ),
+        }
+    }
+}
+
+pub fn run_project(action: &ActionRun, config: &TmsConfig) -> Result<i32, AppError> {
+    let mut launch = prepare_project(action, config)?;
+    let child = launch
+        .command
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .map_err(|e| AppError::Action(format!("Failed to start tomcat: {}", e)))?;
+    launch.log_started(child.id());
+    launch.state(child.id(), false).save()?;
+    if let Some(watcher) = launch.watcher.take() {
+        watcher.start()?;
+    }
+
+    let result = supervise(child, launch.server_port, launch.shutdown_timeout);
+    ProjectState::remove(launch.project_name.as_str())?;
+    result
+}
+
+pub fn start_project(action: &ActionRun, config: &TmsConfig) -> Result<(), AppError> {
+    let mut launch = prepare_project(action, config)?;
+    let console_log = launch.project_cache.join(CONSOLE_LOG);
+    let stdout = std::fs::File::create(console_log.as_path())
+        .map_err(|e| AppError::System(format!("Failed to create console log: {}", e)))?;
+    let stderr = stdout
+        .try_clone()
+        .map_err(|e| AppError::System(format!("Failed to create console log: {}", e)))?;
+    launch
+        .command
+        .stdin(Stdio::null())
+        .stdout(stdout)
+        .stderr(stderr);
+    detach(&mut launch.command);
+
+    let child = launch
+        .command
+        .spawn()
+        .map_err(|e| AppError::Action(format!("Failed to start tomcat: {}", e)))?;
+    launch.log_started(child.id());
+    launch.state(child.id(), true).save()?;
+    log::info!(
+        "Tomcat is running in background, console log: {}",
+        console_log.display()
+    );
+    Ok(())
+}
+
+#[cfg(unix)]
+fn detach(command: &mut Command) {
+    use std::os::unix::process::CommandExt;
+    command.process_group(0);
+}
+
+#[cfg(windows)]
+fn detach(command: &mut Command) {
+    use std::os::windows::process::CommandExt;
+    const DETACHED_PROCESS: u32 = 0x00000008;
+    command.creation_flags(DETACHED_PROCESS);
+}
+
+fn prepare_project(action: &ActionRun, config: &TmsConfig) -> Result<Launch, AppError> {
+    let project_name = action.project.as_deref().unwrap_or_default();
+    let project = super::get_project(config, project_name)?;
+    crate::action::state::ensure_not_running(project.name.as_str())?;
+    let env = match action.env.as_ref() {
+        None => None,
+        Some(env_name) => {
+            log::info!("Using env '{}' of project '{}'", env_name, project.name);
+            Some(super::get_project_env(project, env_name)?)
+        }
+    };
+    let project_items = env.map(|t| &t.items).unwrap_or(&project.items);
     let items = if action.all_items {
-        project.items.clone()
+        project_items.clone()
     } else {
         let mut items = Vec::new();
         for item_name in &action.items {
             let item = super::get_project_item(project, item_name)?;
+            let item = project_items.iter().find(|t| t.name == item.name).unwrap_or(item);
             items.push(item.clone());
         }
         items
@@ -27,37 +175,92 @@ pub fn run_project(action: &ActionRun, config: &TmsConfig) -> Result<(), AppErro
     for item in &items {
         if !PathBuf::from(&item.path).exists() {
             return Err(AppError::Action(format!(
-                "item '{}' does not exist",
-                item.name
+                "item '{}' does not exist: {} (declared in {})",
+                item.name,
+                item.path,
+                project.source.display()
             )));
         }
     }
 
-    let mut runtimes = Vec::new();
-    if project.runtime.is_some() {
-        runtimes.push(project.runtime.as_ref().unwrap());
-    }
-
-    runtimes.push(&config.default);
-    let runtime = RuntimeConfigCombine::new(runtimes);
+    let runtime = RuntimeConfigCombine::for_project(
+        config,
+        project,
+        env,
+        action.runtime.as_deref(),
+    )?;
     let java_home = runtime.java_home()?;
-    let java_options = runtime.java_options();
-    let tomcat_home = runtime.tomcat_home()?;
-    let http_port = if action.http_port.is_some() {
-        check_port("http_port", action.http_port.unwrap())?
+    let mut java_options = runtime.java_options();
+    let tomcat_home = &runtime.tomcat_home()?;
+    match app::version::tomcat_version(Path::new(tomcat_home)) {
+        Ok(tomcat_version) => {
+            log::debug!("Detected Tomcat {} in {}", tomcat_version, tomcat_home);
+            webxml::check_items(&items, tomcat_version.as_str());
+        }
+        Err(e) => log::warn!("Unknown Tomcat version, servlet namespace not checked: {}", e),
+    }
+    let http_port = if let Some(port) = action.http_port {
+        check_port("http_port", port)?
     } else {
         runtime.http_port()?
     };
-    let server_port = if action.server_port.is_some() {
-        check_port("server_port", action.server_port.unwrap())?
+    let server_port = if let Some(port) = action.server_port {
+        check_port("server_port", port)?
     } else {
         runtime.server_port()?
     };
-    let jpda_port = if action.jpda_port.is_some() {
-        check_port("jpda_port", action.jpda_port.unwrap())?
+    let jpda_port = if let Some(port) = action.jpda_port {
+        check_port("jpda_port", port)?
     } else {
         runtime.jpda_port()?
     };
+    let https_port = match action.https_port {
+        Some(port) => Some(check_port("https_port", port)?),
+        None => runtime.https_port()?,
+    };
+    let shutdown_timeout = runtime.shutdown_timeout()?;
+    let mut env = runtime.env()?;
+    let mut system_properties = runtime.system_properties()?;
+    for item in &items {
+        for (name, variable) in &item.env {
+            env.insert(name.clone(), combine::resolve_variable(name, variable)?);
+        }
+        for (name, variable) in &item.system_properties {
+            system_properties.insert(name.clone(), combine::resolve_variable(name, variable)?);
+        }
+    }
+    for (name, value) in &system_properties {
+        java_options.push(format!("-D{}={}", name, value));
+    }
+    log::debug!("Resolved java options: {:?}", &java_options);
+
+    // JPDA port is only bound when running in debug mode
+    let mut ports = vec![("http_port", http_port), ("server_port", server_port)];
+    if action.debug {
+        ports.push(("jpda_port", jpda_port));
+    }
+    if let Some(https_port) = https_port {
+        ports.push(("https_port", https_port));
+    }
+    let names: Vec<&str> = ports.iter().map(|t| t.0).collect();
+    let ports = port::allocate_ports(config, ports, action.auto_ports)?;
+    let allocated = |name| names.iter().position(|t| *t == name).map(|i| ports[i]);
+    let http_port = ports[0];
+    let server_port = ports[1];
+    let jpda_port = allocated("jpda_port").unwrap_or(jpda_port);
+    let https_port = allocated("https_port");
+    let https = match https_port {
+        None => None,
+        Some(https_port) => {
+            let keystore = https::keystore(runtime.https_keystore(), java_home.as_str())?;
+            log::info!(
+                "HTTPS port: {}, keystore: {}",
+                https_port,
+                keystore.file
+            );
+            Some((https_port, keystore))
+        }
+    };
 
     let item_names: Vec<_> = items.iter().map(|t| t.name.as_str()).collect();
     log::info!(
@@ -67,126 +270,349 @@ pub fn run_project(action: &ActionRun, config: &TmsConfig) -> Result<(), AppErro
     );
 
     let project_cache = config::get_cache_dir().join(project.name.as_str());
-    if !project_cache.exists() {
-        log::info!(
-            "Create project cache directory: {}",
-            project_cache.display()
-        );
-        app::util::create_dirs(project_cache.as_path())?;
-    } else {
-        log::info!("Clean project cache: {}", project_cache.display());
-        app::util::remove_dir_items(project_cache.as_path())?;
-    }
+    let cache = cache::ProjectCache::prepare(project_cache.as_path(), action.fresh)?;
+    base::link_shared_libs(project, cache.lib().as_path())?;
 
     let tomcat_conf: PathBuf = PathBuf::from(tomcat_home).join("conf");
-    let cache_conf = project_cache.clone().join("conf");
-    if !cache_conf.exists() {
-        app::util::create_dirs(cache_conf.as_path())?;
+    let staging_conf = cache.staging_conf();
+    copy_tomcat_conf(tomcat_conf.as_path(), staging_conf.as_path())?;
+    if let Some(conf_overlay) = project.conf_overlay.as_ref() {
+        let conf_overlay = Path::new(conf_overlay);
+        if !conf_overlay.is_dir() {
+            return Err(AppError::Action(format!(
+                "conf_overlay is not a directory: {} (declared in {})",
+                conf_overlay.display(),
+                project.source.display()
+            )));
+        }
+        copy_conf_overlay(conf_overlay, staging_conf.as_path())?;
     }
-    let cache_temp = project_cache.clone().join("temp");
-    if !cache_temp.exists() {
-        app::util::create_dirs(cache_temp.as_path())?;
+
+    let mut template_values = BTreeMap::new();
+    template_values.insert("project".to_string(), project.name.clone());
+    template_values.insert("http_port".to_string(), http_port.to_string());
+    template_values.insert("server_port".to_string(), server_port.to_string());
+    template_values.insert("jpda_port".to_string(), jpda_port.to_string());
+    template_values.insert("items".to_string(), item_names.join(","));
+    for item in &items {
+        let prefix = format!("item.{}", item.name);
+        template_values.insert(format!("{}.path", prefix), item.path.clone());
+        template_values.insert(format!("{}.context_path", prefix), item.context_path.clone());
+    }
+    for (name, value) in &env {
+        template_values.insert(format!("env.{}", name), value.clone());
+    }
+    if let Some((https_port, keystore)) = &https {
+        template_values.insert("https_port".to_string(), https_port.to_string());
+        template_values.insert("https_keystore".to_string(), keystore.file.clone());
+        template_values.insert(
+            "https_keystore_password".to_string(),
+            keystore.password.clone(),
+        );
     }
+    for (template, target) in [
+        (project.context_xml_template.as_ref(), CONTEXT_XML),
+        (project.server_xml_template.as_ref(), app::constant::SERVER_XML),
+    ] {
+        if let Some(template) = template {
+            log::debug!("Render {} from template {}", target, template);
+            let content = template::render(Path::new(template), &template_values)?;
+            std::fs::write(staging_conf.join(target), content)
+                .map_err(|e| AppError::Action(format!("Failed to write {}: {}", target, e)))?;
+        }
+    }
+    let server_ports = ServerPorts {
+        http_port,
+        server_port,
+        https,
+    };
+    let (staging_host_conf, https_port) = write_tomcat_server_config(
+        &server_ports,
+        project.server_xml_template.is_some(),
+        staging_conf.as_path(),
+    )?;
+    context::write_item_descriptors(&items, staging_host_conf.as_path())?;
+    let manager = if action.watch {
+        manager::Manager::install(
+            tomcat_home,
+            staging_conf.as_path(),
+            staging_host_conf.as_path(),
+            http_port,
+        )?
+    } else {
+        None
+    };
+    let host_conf = cache.committed_path(staging_host_conf.as_path());
+    let cache_bin = cache.bin();
+    cache.commit()?;
+    let watcher = if action.watch {
+        Some(watch::ItemWatcher::new(
+            manager,
+            &items,
+            host_conf.as_path(),
+        ))
+    } else {
+        None
+    };
 
-    copy_tomcat_conf(tomcat_conf.as_path(), cache_conf.as_path())?;
-    write_tomcat_server_config(&items, http_port, server_port, cache_conf)?;
+    // variables of the launch, mirrored into setenv for running catalina by hand
+    let mut variables = Vec::new();
+    for (name, value) in &env {
+        if RESERVED_ENV.contains(&name.as_str()) {
+            log::warn!("Environment variable '{}' is managed by tms, ignored", name);
+            continue;
+        }
+        log::debug!("Set environment variable: {}", name);
+        variables.push((name.clone(), value.clone()));
+    }
+    variables.push((app::constant::JAVA_HOME.to_string(), java_home));
+    variables.push((
+        app::constant::CATALINA_OPTS.to_string(),
+        join_java_options(&java_options)?,
+    ));
+    if action.debug {
+        variables.push((
+            app::constant::JPDA_ADDRESS.to_string(),
+            format!("localhost:{}", jpda_port),
+        ));
+    }
+    base::write_setenv(cache_bin.as_path(), tomcat_home, &variables)?;
 
     let catalina_exe = PathBuf::from(tomcat_home).join(app::constant::CATALINA_BIN);
-    let mut command = std::process::Command::new(catalina_exe.display().to_string());
+    let mut command = Command::new(catalina_exe.display().to_string());
     command
-        .env(app::constant::JAVA_HOME, java_home)
+        .envs(variables)
         .env(app::constant::CATALINA_HOME, tomcat_home)
         .env(app::constant::CATALINA_BASE, project_cache.display().to_string())
-        .env(app::constant::CATALINA_OPTS, java_options)
         .env(app::constant::TITLE, project.name.as_str());
-
     if action.debug {
-        command
-            .env(app::constant::JPDA_ADDRESS, format!("localhost:{}", jpda_port))
-            .arg("jpda");
+        command.arg("jpda");
     }
+    command.arg("run");
 
-    let child = command
-        .arg("run")
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
-        .spawn()
-        .map_err(|e| AppError::Action(format!("Failed to start tomcat: {}", e)))?;
-    if action.debug {
-        log::info!(
-            "Starting Tomcat > PID: {}, HTTP port: {}, JPDA port: {}",
-            child.id(),
-            http_port,
-            jpda_port
-        );
-    } else {
-        log::info!(
-            "Starting Tomcat > PID: {}, HTTP port: {}",
-            child.id(),
-            http_port
+    Ok(Launch {
+        command,
+        project_name: project.name.clone(),
+        project_cache,
+        items: item_names.iter().map(|t| t.to_string()).collect(),
+        http_port,
+        server_port,
+        jpda_port: if action.debug { Some(jpda_port) } else { None },
+        https_port,
+        shutdown_timeout,
+        watcher,
+    })
+}
+
+// catalina.sh evaluates CATALINA_OPTS with the shell, catalina.bat passes it to the command line
+#[cfg(not(windows))]
+fn join_java_options(options: &[String]) -> Result<String, AppError> {
+    shlex::try_join(options.iter().map(|t| t.as_str()))
+        .map_err(|e| AppError::Action(format!("Invalid java options {:?}: {}", options, e)))
+}
+
+#[cfg(windows)]
+fn join_java_options(options: &[String]) -> Result<String, AppError> {
+    let options: Vec<String> = options
+        .iter()
+        .map(|t| {
+            if t.contains(' ') && !t.starts_with('"') {
+                format!("\"{}\"", t)
+            } else {
+                t.clone()
+            }
+        })
+        .collect();
+    Ok(options.join(" "))
+}
+
+fn supervise(mut child: Child, server_port: u32, shutdown_timeout: u64) -> Result<i32, AppError> {
+    let (sender, receiver) = mpsc::channel();
+    ctrlc::set_handler(move || {
+        let _ = sender.send(());
+    })
+    .map_err(|e| AppError::System(format!("Failed to set signal handler: {}", e)))?;
+
+    loop {
+        if let Some(status) = app::process::try_wait(&mut child)? {
+            log::info!("Tomcat exited with {}", status);
+            return Ok(app::process::exit_code(status));
+        }
+        if receiver.recv_timeout(app::process::POLL_INTERVAL).is_ok() {
+            break;
+        }
+    }
+
+    log::info!("Stopping Tomcat > PID: {}", child.id());
+    if let Err(e) = app::process::send_shutdown(server_port) {
+        log::debug!("{}, forwarding termination signal instead", e);
+        app::process::terminate(child.id())?;
+    }
+
+    let deadline = Instant::now() + Duration::from_secs(shutdown_timeout);
+    while Instant::now() < deadline {
+        if let Some(status) = app::process::try_wait(&mut child)? {
+            log::info!("Tomcat stopped with {}", status);
+            return Ok(app::process::exit_code(status));
+        }
+        if receiver.recv_timeout(app::process::POLL_INTERVAL).is_ok() {
+            log::warn!("Received another signal, killing Tomcat");
+            break;
+        }
+    }
+
+    if Instant::now() >= deadline {
+        log::warn!(
+            "Tomcat did not stop within {} seconds, killing PID {}",
+            shutdown_timeout,
+            child.id()
         );
     }
-    Ok(())
+    child
+        .kill()
+        .map_err(|e| AppError::System(format!("Failed to kill tomcat: {}", e)))?;
+    let status = child
+        .wait()
+        .map_err(|e| AppError::System(format!("Failed to wait for tomcat: {}", e)))?;
+    Ok(app::process::exit_code(status))
 }
 
+// the ports of a server.xml rendered from a template are left alone but must be the allocated
+// ones, only the HTTPS connector is added when the template does not declare one on https_port.
+// Returns the host conf directory and the HTTPS port actually served.
 fn write_tomcat_server_config(
-    items: &Vec<ProjectItemConfig>,
-    http_port: u32,
-    server_port: u32,
-    cache_conf: PathBuf,
-) -> Result<(), AppError> {
+    ports: &ServerPorts,
+    templated: bool,
+    cache_conf: &Path,
+) -> Result<(PathBuf, Option<u32>), AppError> {
     let cache_server_xml = cache_conf.join(app::constant::SERVER_XML);
     let mut server_xml_doc = xml_doc::Document::parse_file(cache_server_xml.as_path())
         .map_err(|e| AppError::Action(format!("Failed to parse server.xml: {:?}", e)))?;
     let root_element = server_xml_doc.root_element().unwrap();
-    root_element.set_attribute(&mut server_xml_doc, "port", server_port.to_string());
+    if !templated {
+        root_element.set_attribute(&mut server_xml_doc, "port", ports.server_port.to_string());
+    } else if root_element.attribute(&server_xml_doc, "port")
+        != Some(ports.server_port.to_string().as_str())
+    {
+        // stop sends the shutdown command to server_port
+        return Err(AppError::Config(format!(
+            "Server port of server_xml_template must be {{{{server_port}}}} ({})",
+            ports.server_port
+        )));
+    }
+    let mut host_conf = None;
+    let mut http_connectors = 0;
+    let mut https_added = false;
+    let mut https_declared = false;
     for service_element in root_element.find_all(&server_xml_doc, "Service") {
         if service_element.attribute(&server_xml_doc, "name") == Some("Catalina") {
             for connector_element in service_element.find_all(&server_xml_doc, "Connector") {
-                if connector_element.attribute(&server_xml_doc, "protocol") == Some("HTTP/1.1") {
+                if templated {
+                    let port = connector_element.attribute(&server_xml_doc, "port");
+                    if let Some((https_port, _)) = &ports.https {
+                        https_declared |= port == Some(https_port.to_string().as_str());
+                    }
+                    if port == Some(ports.http_port.to_string().as_str())
+                        && is_http_connector(&server_xml_doc, connector_element)
+                    {
+                        http_connectors += 1;
+                    }
+                } else if is_http_connector(&server_xml_doc, connector_element) {
                     connector_element.set_attribute(
                         &mut server_xml_doc,
                         "port",
-                        http_port.to_string(),
+                        ports.http_port.to_string(),
                     );
+                    // CONFIDENTIAL constraints redirect to the HTTPS connector
+                    if let Some((https_port, _)) = &ports.https {
+                        connector_element.set_attribute(
+                            &mut server_xml_doc,
+                            "redirectPort",
+                            https_port.to_string(),
+                        );
+                    }
+                    http_connectors += 1;
+                }
+            }
+            if let Some((https_port, keystore)) = &ports.https {
+                if !https_added && !https_declared {
+                    https::add_https_connector(
+                        &mut server_xml_doc,
+                        service_element,
+                        *https_port,
+                        keystore,
+                    )?;
+                    https_added = true;
                 }
             }
 
             for engine_element in service_element.find_all(&server_xml_doc, "Engine") {
                 if engine_element.attribute(&server_xml_doc, "name") == Some("Catalina") {
                     if let Some(host_element) = engine_element.find(&server_xml_doc, "Host") {
-                        for item in items {
-                            let context_element =
-                                xml_doc::Element::build(&mut server_xml_doc, "Context")
-                                    .attribute("docBase", item.path.to_string())
-                                    .attribute("path", item.context_path.as_str())
-                                    .attribute("reloadable", "true")
-                                    .finish();
-                            host_element
-                                .push_child(
-                                    &mut server_xml_doc,
-                                    xml_doc::Node::Element(context_element),
-                                )
-                                .map_err(|e| {
-                                    AppError::Action(format!(
-                                        "Failed to add context to server.xml: {:?}",
-                                        e
-                                    ))
-                                })?;
-                        }
+                        let host_name = host_element
+                            .attribute(&server_xml_doc, "name")
+                            .unwrap_or(DEFAULT_HOST);
+                        host_conf = Some(cache_conf.join("Catalina").join(host_name));
                     }
                 }
             }
         }
     }
 
-    // save server.xml
-    let new_server_xml = server_xml_doc
-        .write_str()
-        .map_err(|e| AppError::Action(format!("Failed to write server.xml: {:?}", e)))?;
-    std::fs::write(cache_server_xml.as_path(), new_server_xml)
-        .map_err(|e| AppError::Action(format!("Failed to write server.xml: {:?}", e)))?;
-    Ok(())
+    if templated && http_connectors == 0 {
+        return Err(AppError::Config(format!(
+            "No HTTP connector of server_xml_template uses {{{{http_port}}}} ({})",
+            ports.http_port
+        )));
+    }
+    if !templated && http_connectors == 0 {
+        log::warn!(
+            "No HTTP connector found in server.xml, http_port {} is not applied",
+            ports.http_port
+        );
+    }
+    let https_port = match &ports.https {
+        Some((https_port, _)) if https_added || https_declared => Some(*https_port),
+        Some((https_port, _)) => {
+            log::warn!(
+                "No Catalina service found in server.xml, https_port {} is not applied",
+                https_port
+            );
+            None
+        }
+        None => None,
+    };
+
+    if !templated || https_added {
+        // save server.xml
+        let new_server_xml = server_xml_doc
+            .write_str()
+            .map_err(|e| AppError::Action(format!("Failed to write server.xml: {:?}", e)))?;
+        std::fs::write(cache_server_xml.as_path(), new_server_xml)
+            .map_err(|e| AppError::Action(format!("Failed to write server.xml: {:?}", e)))?;
+    }
+
+    match host_conf {
+        None => Err(AppError::Action(
+            "No Host found in Catalina engine of server.xml".to_string(),
+        )),
+        Some(host_conf) => {
+            app::util::create_dirs(host_conf.as_path())?;
+            Ok((host_conf, https_port))
+        }
+    }
+}
+
+// plain HTTP connectors, either "HTTP/1.1" or one of the org.apache.coyote.http11 classes,
+// a connector without protocol defaults to HTTP/1.1
+fn is_http_connector(doc: &xml_doc::Document, connector: xml_doc::Element) -> bool {
+    let is_http = match connector.attribute(doc, "protocol") {
+        None => true,
+        Some(protocol) => {
+            protocol == "HTTP/1.1" || protocol.starts_with("org.apache.coyote.http11.")
+        }
+    };
+    is_http && connector.attribute(doc, "SSLEnabled") != Some("true")
 }
 
 fn copy_tomcat_conf(tomcat_conf: &Path, cache_conf: &Path) -> Result<(), AppError> {
@@ -228,3 +654,45 @@ fn copy_tomcat_conf(tomcat_conf: &Path, cache_conf: &Path) -> Result<(), AppErro
     }
     Ok(())
 }
+
+// files of the overlay replace the ones copied from Tomcat, sub directories are merged
+fn copy_conf_overlay(conf_overlay: &Path, cache_conf: &Path) -> Result<(), AppError> {
+    let entries = std::fs::read_dir(conf_overlay).map_err(|e| {
+        AppError::Action(format!(
+            "Failed to read conf overlay {}: {}",
+            conf_overlay.display(),
+            e
+        ))
+    })?;
+    for entry in entries {
+        let entry_path = match entry {
+            Ok(entry) => entry.path(),
+            Err(e) => {
+                return Err(AppError::Action(format!(
+                    "Failed to read conf overlay {}: {}",
+                    conf_overlay.display(),
+                    e
+                )))
+            }
+        };
+        let cache_path = cache_conf.join(entry_path.file_name().unwrap());
+        if entry_path.is_dir() {
+            app::util::create_dirs(cache_path.as_path())?;
+            copy_conf_overlay(entry_path.as_path(), cache_path.as_path())?;
+            continue;
+        }
+        log::debug!(
+            "Copy conf overlay file: {} -> {}",
+            entry_path.display(),
+            cache_path.display()
+        );
+        std::fs::copy(entry_path.as_path(), cache_path.as_path()).map_err(|e| {
+            AppError::Action(format!(
+                "Failed to copy conf overlay file {}: {}",
+                entry_path.display(),
+                e
+            ))
+        })?;
+    }
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use crate::action::state::ProjectState;
+use crate::app;
+use crate::app::AppError;
+use crate::config::TmsConfig;
+
+pub fn allocate_ports(
+    config: &TmsConfig,
+    ports: Vec<(&str, u32)>,
+    auto_ports: bool,
+) -> Result<Vec<u32>, AppError> {
+    let mut allocated: Vec<(&str, u32)> = Vec::new();
+    for (name, port) in ports {
+        let mut candidate = port;
+        loop {
+            let owner = match allocated.iter().find(|(_, t)| *t == candidate) {
+                Some((other, _)) => Some(format!("{} of this project", other)),
+                None => find_owner(config, candidate),
+            };
+            let owner = match owner {
+                None => break,
+                Some(owner) => owner,
+            };
+            if !auto_ports {
+                return Err(AppError::Action(format!(
+                    "{} {} is already in use by {}, use --auto-ports to pick a free port",
+                    name, candidate, owner
+                )));
+            }
+            log::debug!("{} {} is in use by {}", name, candidate, owner);
+            candidate += 1;
+            if !app::util::is_valid_port(candidate) {
+                return Err(AppError::Action(format!(
+                    "No free port found for {} starting from {}",
+                    name, port
+                )));
+            }
+        }
+
+        if candidate != port {
+            log::info!("{} {} is in use, using {} instead", name, port, candidate);
+        }
+        allocated.push((name, candidate));
+    }
+    Ok(allocated.into_iter().map(|(_, port)| port).collect())
+}
+
+fn find_owner(config: &TmsConfig, port: u32) -> Option<String> {
+    if app::util::is_port_free(port) {
+        return None;
+    }
+
+    for project in &config.projects {
+        if let Ok(Some(state)) = ProjectState::load(project.name.as_str()) {
+            let ports = [
+                Some(state.http_port),
+                Some(state.server_port),
+                state.jpda_port,
+                state.https_port,
+            ];
+            if ports.contains(&Some(port)) && state.is_alive() {
+                return Some(format!("project '{}' (PID: {})", state.project, state.pid));
+            }
+        }
+    }
+
+    match app::process::find_port_owner(port) {
+        Some((pid, name)) => Some(format!("process '{}' (PID: {})", name, pid)),
+        None => Some("another process".to_string()),
+    }
+}
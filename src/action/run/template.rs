@@ -0,0 +1,163 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use crate::app::AppError;
+
+const XML_DECLARATION: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n";
+
+// `{{ name }}` placeholders, `${...}` is left alone for Tomcat's own property replacement.
+// Values are XML escaped, `env.NAME` falls back to the environment of tms.
+pub fn render(template: &Path, values: &BTreeMap<String, String>) -> Result<String, AppError> {
+    let content = std::fs::read_to_string(template).map_err(|e| {
+        AppError::Action(format!(
+            "Failed to read template {}: {}",
+            template.display(),
+            e
+        ))
+    })?;
+    render_with(template, content.as_str(), values, |name| {
+        std::env::var(name).ok()
+    })
+}
+
+fn render_with(
+    template: &Path,
+    content: &str,
+    values: &BTreeMap<String, String>,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, AppError> {
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        result.push_str(&rest[..start]);
+        let end = match rest[start..].find("}}") {
+            Some(end) => start + end,
+            None => {
+                return Err(AppError::Action(format!(
+                    "Unclosed '{{{{' in template {}",
+                    template.display()
+                )))
+            }
+        };
+        let name = rest[start + 2..end].trim();
+        let value = match values.get(name) {
+            Some(value) => value.clone(),
+            None => match name.strip_prefix("env.").and_then(&lookup) {
+                Some(value) => value,
+                None => {
+                    return Err(AppError::Action(format!(
+                        "Unknown placeholder '{}' in template {}",
+                        name,
+                        template.display()
+                    )))
+                }
+            },
+        };
+        result.push_str(escape_xml(value.as_str()).as_str());
+        rest = &rest[end + 2..];
+    }
+    result.push_str(rest);
+    // server.xml is parsed again afterwards, which requires the declaration at the very start
+    result.drain(..result.len() - result.trim_start().len());
+    if !result.starts_with("<?xml") {
+        result.insert_str(0, XML_DECLARATION);
+    }
+    Ok(result)
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "HOME" => Some("/home/tms".to_string()),
+            _ => None,
+        }
+    }
+
+    fn render_str(content: &str) -> Result<String, AppError> {
+        let values = BTreeMap::from([
+            ("http_port".to_string(), "8080".to_string()),
+            ("env.HOME".to_string(), "/srv".to_string()),
+            ("item.a.path".to_string(), "/a&b/<c>\"d'".to_string()),
+        ]);
+        render_with(Path::new("server.xml"), content, &values, lookup)
+    }
+
+    #[test]
+    fn render_placeholders() {
+        assert_eq!(
+            render_str(
+                "<?xml version=\"1.0\"?><C port=\"{{ http_port }}\" d=\"${catalina.base}\"/>"
+            )
+            .unwrap(),
+            "<?xml version=\"1.0\"?><C port=\"8080\" d=\"${catalina.base}\"/>"
+        );
+    }
+
+    #[test]
+    fn escape_values() {
+        assert_eq!(
+            render_str("<?xml?><C d=\"{{item.a.path}}\"/>").unwrap(),
+            "<?xml?><C d=\"/a&amp;b/&lt;c&gt;&quot;d&apos;\"/>"
+        );
+    }
+
+    #[test]
+    fn env_fallback() {
+        // configured env variables are passed as values and win over the environment
+        assert!(render_str("<?xml?>{{env.HOME}}").unwrap().ends_with("/srv"));
+        let values = BTreeMap::new();
+        let result = render_with(
+            Path::new("server.xml"),
+            "<?xml?>{{env.HOME}}",
+            &values,
+            lookup,
+        );
+        assert!(result.unwrap().ends_with("/home/tms"));
+        let error = render_str("<?xml?>{{env.MISSING}}")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            error.contains("Unknown placeholder 'env.MISSING'"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn reject_unknown_and_unclosed() {
+        let error = render_str("<?xml?>{{nope}}").unwrap_err().to_string();
+        assert!(error.contains("Unknown placeholder 'nope'"), "{}", error);
+        let error = render_str("<?xml?>{{http_port").unwrap_err().to_string();
+        assert!(error.contains("Unclosed"), "{}", error);
+    }
+
+    #[test]
+    fn add_xml_declaration() {
+        assert_eq!(
+            render_str("\n  <Server/>").unwrap(),
+            format!("{}<Server/>", XML_DECLARATION)
+        );
+        assert_eq!(
+            render_str("\n<?xml version=\"1.1\"?><Server/>").unwrap(),
+            "<?xml version=\"1.1\"?><Server/>"
+        );
+    }
+}
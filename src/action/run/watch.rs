@@ -0,0 +1,127 @@
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::mpsc::RecvTimeoutError;
+use std::time::{Duration, SystemTime};
+
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::action::run::context;
+use crate::action::run::manager::Manager;
+use crate::app::AppError;
+use crate::config::ProjectItemConfig;
+
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+struct WatchedItem {
+    name: String,
+    path: PathBuf,
+    context_path: String,
+    descriptor: PathBuf,
+}
+
+pub struct ItemWatcher {
+    manager: Option<Manager>,
+    items: Vec<WatchedItem>,
+}
+
+impl ItemWatcher {
+    pub fn new(
+        manager: Option<Manager>,
+        items: &[ProjectItemConfig],
+        host_conf: &Path,
+    ) -> ItemWatcher {
+        let items = items
+            .iter()
+            .map(|item| {
+                let path = PathBuf::from(&item.path);
+                WatchedItem {
+                    name: item.name.clone(),
+                    path: path.canonicalize().unwrap_or(path),
+                    context_path: item.context_path.clone(),
+                    descriptor: context::descriptor_path(host_conf, item.context_path.as_str()),
+                }
+            })
+            .collect();
+        ItemWatcher { manager, items }
+    }
+
+    pub fn start(self) -> Result<(), AppError> {
+        let (sender, receiver) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender)
+            .map_err(|e| AppError::System(format!("Failed to create file watcher: {}", e)))?;
+        for item in &self.items {
+            watcher
+                .watch(item.path.as_path(), RecursiveMode::Recursive)
+                .map_err(|e| {
+                    AppError::System(format!(
+                        "Failed to watch item '{}' ({}): {}",
+                        item.name,
+                        item.path.display(),
+                        e
+                    ))
+                })?;
+            log::info!("Watching item '{}': {}", item.name, item.path.display());
+        }
+
+        std::thread::spawn(move || {
+            // keep the watcher alive as long as the thread
+            let _watcher = watcher;
+            let mut pending = BTreeSet::new();
+            loop {
+                match receiver.recv_timeout(DEBOUNCE) {
+                    Ok(Ok(event)) => {
+                        if !matches!(
+                            event.kind,
+                            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                        ) {
+                            continue;
+                        }
+                        for path in &event.paths {
+                            for (i, item) in self.items.iter().enumerate() {
+                                if path.starts_with(&item.path) {
+                                    log::debug!("Changed: {}", path.display());
+                                    pending.insert(i);
+                                }
+                            }
+                        }
+                    }
+                    Ok(Err(e)) => log::warn!("File watcher error: {}", e),
+                    Err(RecvTimeoutError::Timeout) => {
+                        for i in std::mem::take(&mut pending) {
+                            self.reload(&self.items[i]);
+                        }
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+            }
+        });
+        Ok(())
+    }
+
+    fn reload(&self, item: &WatchedItem) {
+        log::info!("Reloading item '{}' ({})", item.name, item.context_path);
+        let result = match &self.manager {
+            Some(manager) => manager.reload(item.context_path.as_str()),
+            None => touch(item.descriptor.as_path()),
+        };
+        if let Err(e) = result {
+            log::warn!("{}", e);
+        }
+    }
+}
+
+fn touch(path: &Path) -> Result<(), AppError> {
+    File::options()
+        .write(true)
+        .open(path)
+        .and_then(|file| file.set_modified(SystemTime::now()))
+        .map_err(|e| {
+            AppError::Action(format!(
+                "Failed to touch context descriptor {}: {}",
+                path.display(),
+                e
+            ))
+        })
+}
@@ -0,0 +1,141 @@
+use std::io::Read;
+use std::path::Path;
+
+use crate::app::version;
+use crate::config::ProjectItemConfig;
+
+const WEB_XML: &str = "WEB-INF/web.xml";
+const JAKARTA_SCHEMA: &str = "jakarta.ee/xml/ns/jakartaee";
+const JAVAX_SCHEMAS: [&str; 2] = ["java.sun.com/xml/ns/", "xmlns.jcp.org/xml/ns/javaee"];
+// Java EE packages renamed to jakarta.*, javax.sql and javax.naming are part of the JDK
+const MOVED_PACKAGES: [&str; 5] = [
+    "javax.servlet.",
+    "javax.mail.",
+    "javax.el.",
+    "javax.transaction.",
+    "javax.persistence.",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Namespace {
+    Javax,
+    Jakarta,
+}
+
+impl Namespace {
+    // Tomcat 10 moved from javax.servlet to jakarta.servlet
+    pub fn of_tomcat(tomcat_version: &str) -> Namespace {
+        match version::version_parts(tomcat_version).first() {
+            Some(major) if *major >= 10 => Namespace::Jakarta,
+            _ => Namespace::Javax,
+        }
+    }
+
+    fn package(&self) -> &'static str {
+        match self {
+            Namespace::Javax => "javax.servlet",
+            Namespace::Jakarta => "jakarta.servlet",
+        }
+    }
+}
+
+pub fn check_items(items: &[ProjectItemConfig], tomcat_version: &str) {
+    let expected = Namespace::of_tomcat(tomcat_version);
+    for item in items {
+        if expected == Namespace::Jakarta {
+            check_resources(item, tomcat_version);
+        }
+        let namespace = match read_web_xml(Path::new(&item.path)).and_then(|t| detect(&t)) {
+            Some(namespace) => namespace,
+            None => continue,
+        };
+        if namespace != expected {
+            log::warn!(
+                "item '{}' targets {} in {}, but Tomcat {} uses {}",
+                item.name,
+                namespace.package(),
+                WEB_XML,
+                tomcat_version,
+                expected.package()
+            );
+        }
+    }
+}
+
+fn check_resources(item: &ProjectItemConfig, tomcat_version: &str) {
+    for resource in &item.context.resources {
+        for (key, value) in resource {
+            if MOVED_PACKAGES.iter().any(|t| value.starts_with(t)) {
+                log::warn!(
+                    "item '{}' uses {}=\"{}\" in a resource, but Tomcat {} expects jakarta.*",
+                    item.name,
+                    key,
+                    value,
+                    tomcat_version
+                );
+            }
+        }
+    }
+}
+
+// web.xml of an exploded webapp or a WAR file, webapps without one are not checked
+fn read_web_xml(path: &Path) -> Option<String> {
+    if path.is_dir() {
+        return std::fs::read_to_string(path.join(WEB_XML)).ok();
+    }
+    let file = std::fs::File::open(path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+    let mut entry = archive.by_name(WEB_XML).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+// class references decide first, the schema only tells which spec the descriptor was written for
+fn detect(web_xml: &str) -> Option<Namespace> {
+    if web_xml.contains("jakarta.servlet.") {
+        Some(Namespace::Jakarta)
+    } else if web_xml.contains("javax.servlet.") {
+        Some(Namespace::Javax)
+    } else if web_xml.contains(JAKARTA_SCHEMA) {
+        Some(Namespace::Jakarta)
+    } else if JAVAX_SCHEMAS.iter().any(|t| web_xml.contains(t)) {
+        Some(Namespace::Javax)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn namespace_of_tomcat() {
+        assert_eq!(Namespace::of_tomcat("9.0.80"), Namespace::Javax);
+        assert_eq!(Namespace::of_tomcat("10.1.15"), Namespace::Jakarta);
+        assert_eq!(Namespace::of_tomcat("11.0.0"), Namespace::Jakarta);
+    }
+
+    #[test]
+    fn detect_by_classes() {
+        let javax = r#"<web-app xmlns="http://xmlns.jcp.org/xml/ns/javaee" version="4.0">
+            <filter><filter-class>javax.servlet.Filter</filter-class></filter>
+        </web-app>"#;
+        assert_eq!(detect(javax), Some(Namespace::Javax));
+        // a class reference wins over the schema
+        let jakarta = r#"<web-app xmlns="http://xmlns.jcp.org/xml/ns/javaee" version="4.0">
+            <listener><listener-class>jakarta.servlet.ServletContextListener</listener-class></listener>
+        </web-app>"#;
+        assert_eq!(detect(jakarta), Some(Namespace::Jakarta));
+    }
+
+    #[test]
+    fn detect_by_schema() {
+        let jakarta = r#"<web-app xmlns="https://jakarta.ee/xml/ns/jakartaee" version="6.0"/>"#;
+        assert_eq!(detect(jakarta), Some(Namespace::Jakarta));
+        let javax = r#"<web-app xmlns="http://java.sun.com/xml/ns/javaee" version="3.0"/>"#;
+        assert_eq!(detect(javax), Some(Namespace::Javax));
+        assert_eq!(detect("<web-app/>"), None);
+    }
+}
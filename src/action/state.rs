@@ -0,0 +1,114 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppError;
+use crate::config;
+
+const STATE_FILENAME: &str = "tms-state.toml";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProjectState {
+    pub project: String,
+    pub pid: u32,
+    pub detached: bool,
+    pub http_port: u32,
+    pub server_port: u32,
+    pub jpda_port: Option<u32>,
+    pub https_port: Option<u32>,
+    pub shutdown_timeout: u64,
+    pub items: Vec<String>,
+    pub started_at: u64,
+}
+
+impl ProjectState {
+    pub fn state_file(project_name: &str) -> PathBuf {
+        config::get_cache_dir()
+            .join(project_name)
+            .join(STATE_FILENAME)
+    }
+
+    pub fn load(project_name: &str) -> Result<Option<ProjectState>, AppError> {
+        let state_file = Self::state_file(project_name);
+        if !state_file.exists() {
+            return Ok(None);
+        }
+        let content = std::fs::read_to_string(state_file.as_path())
+            .map_err(|e| AppError::System(format!("Failed to read state file: {}", e)))?;
+        let state = toml::from_str::<ProjectState>(content.as_str()).map_err(|e| {
+            AppError::System(format!(
+                "Failed to parse state file {}: {}",
+                state_file.display(),
+                e
+            ))
+        })?;
+        Ok(Some(state))
+    }
+
+    pub fn save(&self) -> Result<(), AppError> {
+        let state_file = Self::state_file(self.project.as_str());
+        let content = toml::to_string(self)
+            .map_err(|e| AppError::System(format!("Failed to serialize state file: {}", e)))?;
+        std::fs::write(state_file.as_path(), content)
+            .map_err(|e| AppError::System(format!("Failed to write state file: {}", e)))
+    }
+
+    pub fn remove(project_name: &str) -> Result<(), AppError> {
+        let state_file = Self::state_file(project_name);
+        if !state_file.exists() {
+            return Ok(());
+        }
+        std::fs::remove_file(state_file.as_path())
+            .map_err(|e| AppError::System(format!("Failed to remove state file: {}", e)))
+    }
+
+    // a pid of a rebooted machine may have been reused by another process
+    pub fn is_alive(&self) -> bool {
+        let base = config::get_cache_dir().join(self.project.as_str());
+        crate::app::process::is_alive(self.pid)
+            && crate::app::process::is_tomcat(self.pid, base.as_path()).unwrap_or(true)
+    }
+
+    pub fn uptime(&self) -> u64 {
+        now().saturating_sub(self.started_at)
+    }
+}
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|t| t.as_secs())
+        .unwrap_or(0)
+}
+
+pub fn format_duration(seconds: u64) -> String {
+    let hours = seconds / 3600;
+    let minutes = seconds % 3600 / 60;
+    let seconds = seconds % 60;
+    if hours > 0 {
+        format!("{}h {:02}m {:02}s", hours, minutes, seconds)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+pub fn ensure_not_running(project_name: &str) -> Result<(), AppError> {
+    if let Some(state) = ProjectState::load(project_name)? {
+        if state.is_alive() {
+            return Err(AppError::Action(format!(
+                "Project '{}' is already running (PID: {}, HTTP port: {})",
+                project_name, state.pid, state.http_port
+            )));
+        }
+        log::debug!(
+            "Remove stale state of project '{}' (PID: {})",
+            project_name,
+            state.pid
+        );
+        ProjectState::remove(project_name)?;
+    }
+    Ok(())
+}
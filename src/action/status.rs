@@ -0,0 +1,66 @@
+use prettytable::{row, Table};
+
+use crate::action::state::{format_duration, ProjectState};
+use crate::app::AppError;
+use crate::config::{ProjectConfig, TmsConfig};
+
+pub fn status_projects(config: &TmsConfig) -> Result<(), AppError> {
+    print_status(config.projects.iter().collect())
+}
+
+pub fn status_project(config: &TmsConfig, project_name: &str) -> Result<(), AppError> {
+    let project = super::get_project(config, project_name)?;
+    print_status(vec![project])
+}
+
+fn print_status(projects: Vec<&ProjectConfig>) -> Result<(), AppError> {
+    let mut states = Vec::new();
+    for project in projects {
+        if let Some(state) = ProjectState::load(project.name.as_str())? {
+            states.push(state);
+        }
+    }
+    if states.is_empty() {
+        log::info!("No project is running");
+        return Ok(());
+    }
+
+    let mut table = Table::new();
+    table.add_row(row![
+        "Project", "PID", "Mode", "Status", "HTTP", "HTTPS", "Server", "JPDA", "Items", "Uptime"
+    ]);
+    for state in &states {
+        let mode = if state.detached {
+            "background"
+        } else {
+            "foreground"
+        };
+        let (status, uptime) = if state.is_alive() {
+            ("running", format_duration(state.uptime()))
+        } else {
+            ("dead", String::new())
+        };
+        let jpda_port = match state.jpda_port {
+            Some(port) => port.to_string(),
+            None => String::new(),
+        };
+        let https_port = match state.https_port {
+            Some(port) => port.to_string(),
+            None => String::new(),
+        };
+        table.add_row(row![
+            state.project,
+            state.pid,
+            mode,
+            status,
+            state.http_port,
+            https_port,
+            state.server_port,
+            jpda_port,
+            state.items.join(", "),
+            uptime
+        ]);
+    }
+    table.printstd();
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use crate::action::state::ProjectState;
+use crate::app;
+use crate::config;
+
+pub fn stop_all_projects(config: &config::TmsConfig) -> Result<(), app::AppError> {
+    let mut stopped = 0;
+    for project in &config.projects {
+        if let Some(state) = ProjectState::load(project.name.as_str())? {
+            stop(&state)?;
+            stopped += 1;
+        }
+    }
+    if stopped == 0 {
+        log::info!("No project is running");
+    }
+    Ok(())
+}
+
+pub fn stop_project(config: &config::TmsConfig, project_name: &str) -> Result<(), app::AppError> {
+    let project = super::get_project(config, project_name)?;
+    match ProjectState::load(project.name.as_str())? {
+        None => Err(app::AppError::Action(format!(
+            "Project '{}' is not running",
+            project.name
+        ))),
+        Some(state) => stop(&state),
+    }
+}
+
+fn stop(state: &ProjectState) -> Result<(), app::AppError> {
+    if !state.is_alive() {
+        log::info!(
+            "Project '{}' is not running (PID: {}), remove stale state",
+            state.project,
+            state.pid
+        );
+        return ProjectState::remove(state.project.as_str());
+    }
+
+    log::info!("Stopping project '{}' > PID: {}", state.project, state.pid);
+    let timeout = Duration::from_secs(state.shutdown_timeout);
+    let mut stopped = match app::process::send_shutdown(state.server_port) {
+        Ok(_) => app::process::wait_exit(state.pid, timeout),
+        Err(e) => {
+            log::warn!("{}", e);
+            false
+        }
+    };
+
+    if !stopped {
+        log::warn!("Sending termination signal to PID {}", state.pid);
+        app::process::terminate(state.pid)?;
+        stopped = app::process::wait_exit(state.pid, timeout);
+    }
+
+    if !stopped {
+        log::warn!(
+            "Tomcat did not stop within {} seconds, killing PID {}",
+            state.shutdown_timeout,
+            state.pid
+        );
+        app::process::kill(state.pid)?;
+        app::process::wait_exit(state.pid, timeout);
+    }
+
+    log::info!("Project '{}' stopped", state.project);
+    ProjectState::remove(state.project.as_str())
+}
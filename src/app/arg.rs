@@ -24,16 +24,48 @@ impl Argument {
 pub enum Action {
     #[command(about = "Run web project")]
     Run(ActionRun),
+    #[command(about = "Start web project in background")]
+    Start(ActionRun),
+    #[command(about = "Stop web project started by tms")]
+    Stop(ActionStop),
+    #[command(about = "Show status of running projects")]
+    Status(ActionStatus),
     #[command(about = "Clear project's cache and logs")]
     Clean(ActionClean),
     #[command(about = "List projects or project's items")]
     List(ActionList),
+    #[command(about = "Check projects and their runtime environment")]
+    Doctor,
+    #[command(about = "List JDKs found on this machine")]
+    Jdks,
+    #[command(about = "Manage Tomcat installations")]
+    Tomcat(ActionTomcat),
     #[command(about = "Tms config")]
     Config,
     #[command(about = "Print version")]
     Version,
 }
 
+#[derive(Debug, Args)]
+pub struct ActionTomcat {
+    #[command(subcommand)]
+    pub action: TomcatAction,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TomcatAction {
+    #[command(about = "Install Tomcat from a .tar.gz or .zip distribution")]
+    Add(ActionTomcatAdd),
+    #[command(about = "List installed Tomcat")]
+    List,
+}
+
+#[derive(Debug, Args)]
+pub struct ActionTomcatAdd {
+    #[arg(help = "Path of the Tomcat distribution archive")]
+    pub archive: String,
+}
+
 #[derive(Debug, Args)]
 pub struct ActionList {
     #[arg(help = "Name of the project")]
@@ -50,10 +82,26 @@ pub struct ActionClean {
     pub all: bool,
 }
 
+#[derive(Debug, Args)]
+pub struct ActionStop {
+    #[arg(help = "Name of the project that needs to be stopped")]
+    pub project: Option<String>,
+
+    #[arg(short, long, default_value_t = false)]
+    #[arg(help = "Stop all the running projects")]
+    pub all: bool,
+}
+
+#[derive(Debug, Args)]
+pub struct ActionStatus {
+    #[arg(help = "Name of the project")]
+    pub project: Option<String>,
+}
+
 #[derive(Debug, Args)]
 pub struct ActionRun {
-    #[arg(help = "Name of the project that needs to be run")]
-    pub project: String,
+    #[arg(help = "Name of the project that needs to be run, defaults to the project in tms.toml")]
+    pub project: Option<String>,
 
     #[arg(short, long, default_value_t = false)]
     #[arg(help = "Run all items of the project")]
@@ -67,6 +115,12 @@ pub struct ActionRun {
     #[arg(help = "Run project in debug mode")]
     pub debug: bool,
 
+    #[arg(short, long, help = "Environment of the project to run")]
+    pub env: Option<String>,
+
+    #[arg(long, help = "Runtime profile that overrides the project runtime")]
+    pub runtime: Option<String>,
+
     #[arg(long, help = "Tomcat HTTP port")]
     pub http_port: Option<u32>,
 
@@ -75,6 +129,21 @@ pub struct ActionRun {
 
     #[arg(long, help = "Tomcat JPDA port")]
     pub jpda_port: Option<u32>,
+
+    #[arg(long, help = "Tomcat HTTPS port, enables the HTTPS connector")]
+    pub https_port: Option<u32>,
+
+    #[arg(long, default_value_t = false)]
+    #[arg(help = "Pick the next free ports when the configured ones are in use")]
+    pub auto_ports: bool,
+
+    #[arg(short, long, default_value_t = false)]
+    #[arg(help = "Reload items when their files change")]
+    pub watch: bool,
+
+    #[arg(long, default_value_t = false)]
+    #[arg(help = "Recreate the project cache, including work and logs")]
+    pub fresh: bool,
 }
 
 #[derive(Debug, Args)]
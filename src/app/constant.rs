@@ -9,11 +9,15 @@ pub const TITLE: &str = "TITLE";
 pub const CATALINA_BIN: &str = "bin/catalina.bat";
 #[cfg(target_os = "windows")]
 pub const JAVA_BIN: &str = "bin/java.exe";
+#[cfg(target_os = "windows")]
+pub const KEYTOOL_BIN: &str = "bin/keytool.exe";
 
 #[cfg(not(target_os = "windows"))]
 pub const CATALINA_BIN: &str = "bin/catalina.sh";
 #[cfg(not(target_os = "windows"))]
 pub const JAVA_BIN: &str = "bin/java";
+#[cfg(not(target_os = "windows"))]
+pub const KEYTOOL_BIN: &str = "bin/keytool";
 
 pub const SERVER_XML: &str = "server.xml";
-pub const LOG_CONFIG_FILE: &str = "logging.properties";
+pub const SHUTDOWN_COMMAND: &str = "SHUTDOWN";
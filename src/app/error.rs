@@ -5,6 +5,8 @@ pub enum AppError {
     System(String),
     Argument(String),
     Config(String),
+    // a configuration error already rendered with its file, line and source snippet
+    Located(String),
     Action(String),
 }
 
@@ -17,8 +19,8 @@ impl Display for AppError {
             AppError::Argument(msg) => {
                 write!(f, "argument error: {}", msg)
             }
-            AppError::Config(msg) => {
-                write!(f, "configuration file error:{}", msg)
+            AppError::Config(msg) | AppError::Located(msg) => {
+                write!(f, "configuration file error: {}", msg)
             }
             AppError::Action(msg) => {
                 write!(f, "{}", msg)
@@ -0,0 +1,120 @@
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use crate::app::constant::JAVA_BIN;
+use crate::app::version;
+
+const RELEASE_FILE: &str = "release";
+
+#[derive(Debug, Clone)]
+pub struct Jdk {
+    pub home: PathBuf,
+    pub version: String,
+    pub vendor: String,
+}
+
+// JDKs installed in the usual locations, newest first
+pub fn discover() -> Vec<Jdk> {
+    let mut candidates = Vec::new();
+    if let Ok(java_home) = std::env::var("JAVA_HOME") {
+        candidates.push(PathBuf::from(java_home));
+    }
+    for dir in search_dirs() {
+        let entries = match std::fs::read_dir(dir.as_path()) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        let mut homes: Vec<PathBuf> = entries.flatten().map(|t| t.path()).collect();
+        homes.sort();
+        for home in homes {
+            // macOS bundles keep the JDK in Contents/Home
+            let bundle_home = home.join("Contents").join("Home");
+            candidates.push(if bundle_home.is_dir() {
+                bundle_home
+            } else {
+                home
+            });
+        }
+    }
+
+    let mut seen = HashSet::new();
+    let mut jdks = Vec::new();
+    for home in candidates {
+        // symlinks like sdkman's `current` resolve to the same JDK
+        let real_home = match home.canonicalize() {
+            Ok(real_home) => real_home,
+            Err(_) => continue,
+        };
+        if !seen.insert(real_home.clone()) {
+            continue;
+        }
+        if let Some(jdk) = read_jdk(real_home.as_path()) {
+            jdks.push(jdk);
+        }
+    }
+    jdks.sort_by(|a, b| version::compare_versions(&b.version, &a.version));
+    jdks
+}
+
+fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![
+        PathBuf::from("/usr/lib/jvm"),
+        PathBuf::from("/usr/java"),
+        PathBuf::from("/Library/Java/JavaVirtualMachines"),
+        PathBuf::from("C:\\Program Files\\Java"),
+        PathBuf::from("C:\\Program Files\\Eclipse Adoptium"),
+    ];
+    if let Some(home) = dirs::home_dir() {
+        dirs.push(home.join(".sdkman").join("candidates").join("java"));
+        dirs.push(home.join(".jdks"));
+        dirs.push(
+            home.join("Library")
+                .join("Java")
+                .join("JavaVirtualMachines"),
+        );
+    }
+    let asdf_dir = match std::env::var("ASDF_DATA_DIR") {
+        Ok(dir) => Some(PathBuf::from(dir)),
+        Err(_) => dirs::home_dir().map(|t| t.join(".asdf")),
+    };
+    if let Some(asdf_dir) = asdf_dir {
+        dirs.push(asdf_dir.join("installs").join("java"));
+    }
+    dirs
+}
+
+fn read_jdk(home: &Path) -> Option<Jdk> {
+    if !home.join(JAVA_BIN).is_file() {
+        return None;
+    }
+    let mut version = None;
+    let mut vendor = None;
+    if let Ok(content) = std::fs::read_to_string(home.join(RELEASE_FILE)) {
+        for line in content.lines() {
+            if let Some((key, value)) = line.split_once('=') {
+                let value = value.trim().trim_matches('"').to_string();
+                match key.trim() {
+                    "JAVA_VERSION" => version = Some(value),
+                    "IMPLEMENTOR" => vendor = Some(value),
+                    _ => {}
+                }
+            }
+        }
+    }
+    // fall back to `java -version` for JDKs without a release file
+    let version = match version {
+        Some(version) => version,
+        None => match version::java_version(home) {
+            Ok(version) => version,
+            Err(e) => {
+                log::debug!("Skip JDK candidate {}: {}", home.display(), e);
+                return None;
+            }
+        },
+    };
+    Some(Jdk {
+        home: home.to_path_buf(),
+        version,
+        vendor: vendor.unwrap_or_default(),
+    })
+}
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use clap::Parser;
 
 use crate::action;
@@ -9,6 +11,10 @@ pub mod logger;
 pub mod util;
 pub mod constant;
 pub mod arg;
+pub mod process;
+pub mod version;
+pub mod jdk;
+pub mod tomcat;
 
 pub const VERSION: &str = "0.2.0";
 
@@ -17,15 +23,38 @@ pub const VERSION: &str = "0.2.0";
 #[exclude = "config.toml"]
 struct ExampleConfig;
 
-pub fn run(config: &TmsConfig) -> Result<(), AppError> {
+pub fn run(config: &TmsConfig) -> Result<i32, AppError> {
     let args = arg::Argument::parse();
     match args.action {
         arg::Action::Run(action) => {
-            if !action.all_items && action.items.is_empty() {
-                return Err(AppError::Argument("items is required".to_string()));
+            let action = resolve_run_project(action, config)?;
+            return action::run::run_project(&action, config);
+        }
+        arg::Action::Start(action) => {
+            let action = resolve_run_project(action, config)?;
+            if action.watch {
+                return Err(AppError::Argument(
+                    "watch is only supported by run".to_string(),
+                ));
             }
-            action::run::run_project(&action, config)?;
+            action::run::start_project(&action, config)?;
         }
+        arg::Action::Stop(info) => match &info.project {
+            None => {
+                if info.all {
+                    action::stop::stop_all_projects(config)?;
+                } else {
+                    return Err(AppError::Argument("project name is required".to_string()));
+                }
+            }
+            Some(project) => {
+                action::stop::stop_project(config, project)?;
+            }
+        },
+        arg::Action::Status(action) => match action.project {
+            None => action::status::status_projects(config)?,
+            Some(project) => action::status::status_project(config, &project)?,
+        },
         arg::Action::Clean(info) => match &info.project {
             None => {
                 if info.all {
@@ -42,17 +71,28 @@ pub fn run(config: &TmsConfig) -> Result<(), AppError> {
             None => action::list::list_projects(config),
             Some(project) => action::list::list_project_items(config, &project)?,
         },
-        arg::Action::Config => print_config(),
-        arg::Action::Version => print_version(),
+        arg::Action::Doctor => return action::doctor::doctor(config),
+        action @ (arg::Action::Config
+        | arg::Action::Version
+        | arg::Action::Jdks
+        | arg::Action::Tomcat(_)) => run_without_config(action)?,
     }
 
-    Ok(())
+    Ok(0)
 }
 
 pub fn run_without_config(action: arg::Action) -> Result<(), AppError> {
     match action {
         arg::Action::Config => print_config(),
         arg::Action::Version => print_version(),
+        arg::Action::Jdks => action::list::list_jdks(),
+        arg::Action::Tomcat(tomcat) => match tomcat.action {
+            arg::TomcatAction::Add(add) => {
+                let tomcat = tomcat::install(Path::new(&add.archive))?;
+                println!("Installed Tomcat {} to {}", tomcat.version, tomcat.home.display());
+            }
+            arg::TomcatAction::List => action::list::list_tomcats(),
+        },
         _ => unreachable!(),
     }
     Ok(())
@@ -66,3 +106,36 @@ fn print_config() {
 fn print_version() {
     println!("tms version {}", VERSION);
 }
+
+// without a project argument, run all items of the project defined in tms.toml
+fn resolve_run_project(
+    mut action: arg::ActionRun,
+    config: &TmsConfig,
+) -> Result<arg::ActionRun, AppError> {
+    if action.project.is_none() {
+        match config.local_projects.as_slice() {
+            [] => {
+                return Err(AppError::Argument(
+                    "project name is required, no project found in tms.toml".to_string(),
+                ))
+            }
+            [project] => {
+                log::info!("Using project '{}' from tms.toml", project);
+                action.project = Some(project.clone());
+                if action.items.is_empty() {
+                    action.all_items = true;
+                }
+            }
+            projects => {
+                return Err(AppError::Argument(format!(
+                    "project name is required, tms.toml defines {}",
+                    projects.join(", ")
+                )))
+            }
+        }
+    }
+    if !action.all_items && action.items.is_empty() {
+        return Err(AppError::Argument("items is required".to_string()));
+    }
+    Ok(action)
+}
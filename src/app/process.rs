@@ -0,0 +1,224 @@
+use std::io::Write;
+use std::net::{Ipv4Addr, SocketAddr, TcpStream};
+use std::path::Path;
+use std::process::{Child, ExitStatus};
+use std::time::{Duration, Instant};
+
+use crate::app::constant::SHUTDOWN_COMMAND;
+use crate::app::AppError;
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(3);
+pub const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+pub fn send_shutdown(port: u32) -> Result<(), AppError> {
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port as u16));
+    let mut stream = TcpStream::connect_timeout(&address, CONNECT_TIMEOUT).map_err(|e| {
+        AppError::System(format!("Failed to connect to server port {}: {}", port, e))
+    })?;
+    stream
+        .write_all(SHUTDOWN_COMMAND.as_bytes())
+        .and_then(|_| stream.flush())
+        .map_err(|e| {
+            AppError::System(format!(
+                "Failed to send shutdown command to server port {}: {}",
+                port, e
+            ))
+        })
+}
+
+#[cfg(unix)]
+pub fn terminate(pid: u32) -> Result<(), AppError> {
+    let result = unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+    if result != 0 {
+        return Err(AppError::System(format!(
+            "Failed to terminate process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn terminate(pid: u32) -> Result<(), AppError> {
+    let status = std::process::Command::new("taskkill")
+        .args(["/PID", pid.to_string().as_str(), "/T"])
+        .status()
+        .map_err(|e| AppError::System(format!("Failed to terminate process {}: {}", pid, e)))?;
+    if !status.success() {
+        return Err(AppError::System(format!(
+            "Failed to terminate process {}",
+            pid
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn kill(pid: u32) -> Result<(), AppError> {
+    let result = unsafe { libc::kill(pid as libc::pid_t, libc::SIGKILL) };
+    if result != 0 {
+        return Err(AppError::System(format!(
+            "Failed to kill process {}: {}",
+            pid,
+            std::io::Error::last_os_error()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn kill(pid: u32) -> Result<(), AppError> {
+    let status = std::process::Command::new("taskkill")
+        .args(["/PID", pid.to_string().as_str(), "/T", "/F"])
+        .status()
+        .map_err(|e| AppError::System(format!("Failed to kill process {}: {}", pid, e)))?;
+    if !status.success() {
+        return Err(AppError::System(format!("Failed to kill process {}", pid)));
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn is_alive(pid: u32) -> bool {
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+#[cfg(not(unix))]
+pub fn is_alive(pid: u32) -> bool {
+    let filter = format!("PID eq {}", pid);
+    match std::process::Command::new("tasklist")
+        .args(["/FI", filter.as_str(), "/NH"])
+        .output()
+    {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .split_whitespace()
+            .any(|t| t == pid.to_string()),
+        Err(_) => false,
+    }
+}
+
+// whether the process is the Tomcat of the given CATALINA_BASE: the java process naming it
+// on its command line, or catalina still starting with it in the environment.
+// None when this cannot be told on this platform.
+#[cfg(target_os = "linux")]
+pub fn is_tomcat(pid: u32, catalina_base: &Path) -> Option<bool> {
+    let proc_dir = Path::new("/proc").join(pid.to_string());
+    let cmdline = match std::fs::read(proc_dir.join("cmdline")) {
+        Ok(cmdline) => cmdline,
+        Err(_) => return Some(false),
+    };
+    let base = catalina_base.display().to_string();
+    let args: Vec<String> = cmdline
+        .split(|t| *t == 0)
+        .map(|t| String::from_utf8_lossy(t).to_string())
+        .collect();
+    let is_java = args
+        .first()
+        .and_then(|t| Path::new(t).file_name())
+        .is_some_and(|t| t.to_string_lossy().starts_with("java"));
+    if is_java && args.iter().any(|t| t.contains(base.as_str())) {
+        return Some(true);
+    }
+    let variable = format!("CATALINA_BASE={}", base);
+    let environ = std::fs::read(proc_dir.join("environ")).unwrap_or_default();
+    Some(environ.split(|t| *t == 0).any(|t| t == variable.as_bytes()))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn is_tomcat(_pid: u32, _catalina_base: &Path) -> Option<bool> {
+    None
+}
+
+pub fn wait_exit(pid: u32, timeout: Duration) -> bool {
+    let deadline = Instant::now() + timeout;
+    while Instant::now() < deadline {
+        if !is_alive(pid) {
+            return true;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+    !is_alive(pid)
+}
+
+#[cfg(target_os = "linux")]
+pub fn find_port_owner(port: u32) -> Option<(u32, String)> {
+    let mut sockets = Vec::new();
+    for table in ["/proc/net/tcp", "/proc/net/tcp6"] {
+        let content = match std::fs::read_to_string(table) {
+            Ok(content) => content,
+            Err(_) => continue,
+        };
+        for line in content.lines().skip(1) {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            // only sockets in LISTEN state
+            if fields.len() < 10 || fields[3] != "0A" {
+                continue;
+            }
+            let local_port = fields[1]
+                .rsplit(':')
+                .next()
+                .and_then(|t| u32::from_str_radix(t, 16).ok());
+            if local_port == Some(port) {
+                sockets.push(format!("socket:[{}]", fields[9]));
+            }
+        }
+    }
+    if sockets.is_empty() {
+        return None;
+    }
+
+    for entry in std::fs::read_dir("/proc").ok()?.flatten() {
+        let pid = match entry
+            .file_name()
+            .to_str()
+            .and_then(|t| t.parse::<u32>().ok())
+        {
+            Some(pid) => pid,
+            None => continue,
+        };
+        let fds = match std::fs::read_dir(entry.path().join("fd")) {
+            Ok(fds) => fds,
+            Err(_) => continue,
+        };
+        for fd in fds.flatten() {
+            let target = match std::fs::read_link(fd.path()) {
+                Ok(target) => target,
+                Err(_) => continue,
+            };
+            if sockets.iter().any(|t| target.as_os_str() == t.as_str()) {
+                let name = std::fs::read_to_string(entry.path().join("comm"))
+                    .map(|t| t.trim().to_string())
+                    .unwrap_or_default();
+                return Some((pid, name));
+            }
+        }
+    }
+    None
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn find_port_owner(_port: u32) -> Option<(u32, String)> {
+    None
+}
+
+pub fn try_wait(child: &mut Child) -> Result<Option<ExitStatus>, AppError> {
+    child
+        .try_wait()
+        .map_err(|e| AppError::System(format!("Failed to wait for process {}: {}", child.id(), e)))
+}
+
+pub fn exit_code(status: ExitStatus) -> i32 {
+    if let Some(code) = status.code() {
+        return code;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::process::ExitStatusExt;
+        if let Some(signal) = status.signal() {
+            return 128 + signal;
+        }
+    }
+    1
+}
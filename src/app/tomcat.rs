@@ -0,0 +1,161 @@
+use std::fs::File;
+use std::path::{Path, PathBuf};
+
+use crate::app;
+use crate::app::version;
+use crate::app::version::CATALINA_JAR;
+use crate::app::AppError;
+use crate::config;
+
+const TOMCAT_DIR: &str = "tomcat";
+
+#[derive(Debug, Clone)]
+pub struct Tomcat {
+    pub home: PathBuf,
+    pub version: String,
+}
+
+pub fn get_tomcat_dir() -> PathBuf {
+    config::get_data_dir().join(TOMCAT_DIR)
+}
+
+// Tomcat installations managed by tms, newest first
+pub fn installed() -> Vec<Tomcat> {
+    let entries = match std::fs::read_dir(get_tomcat_dir()) {
+        Ok(entries) => entries,
+        Err(_) => return Vec::new(),
+    };
+    let mut tomcats = Vec::new();
+    for home in entries.flatten().map(|t| t.path()) {
+        if !home.is_dir() || is_temp_dir(home.as_path()) {
+            continue;
+        }
+        match version::tomcat_version(home.as_path()) {
+            Ok(version) => tomcats.push(Tomcat { home, version }),
+            Err(e) => log::debug!("Skip Tomcat candidate {}: {}", home.display(), e),
+        }
+    }
+    tomcats.sort_by(|a, b| version::compare_versions(&b.version, &a.version));
+    tomcats
+}
+
+pub fn install(archive: &Path) -> Result<Tomcat, AppError> {
+    if !archive.is_file() {
+        return Err(AppError::Argument(format!(
+            "archive '{}' does not exist",
+            archive.display()
+        )));
+    }
+    let tomcat_dir = get_tomcat_dir();
+    app::util::create_dirs(tomcat_dir.as_path())?;
+
+    // unpack next to the installations first, so that the final rename stays on one file system
+    let temp_dir = tomcat_dir.join(format!(".unpack-{}", std::process::id()));
+    app::util::remove_dir_items(temp_dir.as_path())?;
+    app::util::create_dirs(temp_dir.as_path())?;
+    let result = unpack(archive, temp_dir.as_path()).and_then(|_| {
+        let home = find_tomcat_home(temp_dir.as_path()).ok_or_else(|| {
+            AppError::Action(format!(
+                "'{}' is not a Tomcat distribution, {} not found",
+                archive.display(),
+                CATALINA_JAR
+            ))
+        })?;
+        let version = version::tomcat_version(home.as_path())?;
+        let target = tomcat_dir.join(version.as_str());
+        if target.exists() {
+            return Err(AppError::Action(format!(
+                "Tomcat {} is already installed at {}",
+                version,
+                target.display()
+            )));
+        }
+        std::fs::rename(home.as_path(), target.as_path()).map_err(|e| {
+            AppError::System(format!(
+                "Failed to move Tomcat to {}: {}",
+                target.display(),
+                e
+            ))
+        })?;
+        Ok(Tomcat {
+            home: target,
+            version,
+        })
+    });
+    app::util::remove_dir_items(temp_dir.as_path())?;
+    let tomcat = result?;
+    make_scripts_executable(tomcat.home.as_path())?;
+    Ok(tomcat)
+}
+
+fn unpack(archive: &Path, target: &Path) -> Result<(), AppError> {
+    let name = archive
+        .file_name()
+        .map(|t| t.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let file = File::open(archive)
+        .map_err(|e| AppError::System(format!("Failed to open {}: {}", archive.display(), e)))?;
+    let unpack_error = |e: &dyn std::fmt::Display| {
+        AppError::System(format!("Failed to unpack {}: {}", archive.display(), e))
+    };
+    if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        let decoder = flate2::read::GzDecoder::new(file);
+        tar::Archive::new(decoder)
+            .unpack(target)
+            .map_err(|e| unpack_error(&e))
+    } else if name.ends_with(".zip") {
+        zip::ZipArchive::new(file)
+            .and_then(|mut zip| zip.extract(target))
+            .map_err(|e| unpack_error(&e))
+    } else {
+        Err(AppError::Argument(format!(
+            "Unsupported archive '{}', expected .tar.gz, .tgz or .zip",
+            archive.display()
+        )))
+    }
+}
+
+// distributions usually contain a single apache-tomcat-x.y.z directory
+fn find_tomcat_home(dir: &Path) -> Option<PathBuf> {
+    if dir.join(CATALINA_JAR).is_file() {
+        return Some(dir.to_path_buf());
+    }
+    let entries = std::fs::read_dir(dir).ok()?;
+    entries
+        .flatten()
+        .map(|t| t.path())
+        .find(|t| t.join(CATALINA_JAR).is_file())
+}
+
+fn is_temp_dir(path: &Path) -> bool {
+    path.file_name()
+        .map(|t| t.to_string_lossy().starts_with('.'))
+        .unwrap_or(false)
+}
+
+#[cfg(unix)]
+fn make_scripts_executable(home: &Path) -> Result<(), AppError> {
+    use std::os::unix::fs::PermissionsExt;
+    let entries = match std::fs::read_dir(home.join("bin")) {
+        Ok(entries) => entries,
+        Err(_) => return Ok(()),
+    };
+    for script in entries.flatten().map(|t| t.path()) {
+        if script.extension().map(|t| t == "sh").unwrap_or(false) {
+            std::fs::set_permissions(script.as_path(), std::fs::Permissions::from_mode(0o755))
+                .map_err(|e| {
+                    AppError::System(format!(
+                        "Failed to set permissions of {}: {}",
+                        script.display(),
+                        e
+                    ))
+                })?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(windows)]
+fn make_scripts_executable(_home: &Path) -> Result<(), AppError> {
+    Ok(())
+}
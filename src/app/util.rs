@@ -1,3 +1,4 @@
+use std::net::{Ipv4Addr, TcpListener};
 use std::path::Path;
 
 use crate::app::AppError;
@@ -16,6 +17,10 @@ pub fn check_port(name: &str, port: u32) -> Result<u32, AppError> {
     Ok(port)
 }
 
+pub fn is_port_free(port: u32) -> bool {
+    TcpListener::bind((Ipv4Addr::UNSPECIFIED, port as u16)).is_ok()
+}
+
 pub fn create_dirs(path: &Path) -> Result<(), AppError> {
     if path.exists() {
         return Ok(());
@@ -36,3 +41,62 @@ pub fn remove_dir_items(path: &Path) -> Result<(), AppError> {
     }
     Ok(())
 }
+
+pub fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let b = [
+            chunk[0],
+            *chunk.get(1).unwrap_or(&0),
+            *chunk.get(2).unwrap_or(&0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - i * 6) & 0x3f) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+// percent-encoding of a URL query value, `/` is kept as Tomcat expects it in context paths
+pub fn url_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for b in value.bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                encoded.push(b as char)
+            }
+            b => encoded.push_str(format!("%{:02X}", b).as_str()),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_rfc4648() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foob"), "Zm9vYg==");
+        assert_eq!(base64_encode(b"fooba"), "Zm9vYmE=");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn url_encode_context_paths() {
+        assert_eq!(url_encode("/app"), "/app");
+        assert_eq!(url_encode("/a/b-c_d.e~"), "/a/b-c_d.e~");
+        assert_eq!(url_encode("/my app#1%"), "/my%20app%231%25");
+        assert_eq!(url_encode("/é"), "/%C3%A9");
+    }
+}
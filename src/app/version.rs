@@ -0,0 +1,225 @@
+use std::cmp::Ordering;
+use std::io::Read;
+use std::path::Path;
+use std::process::Command;
+
+use crate::app::constant::JAVA_BIN;
+use crate::app::AppError;
+
+pub const CATALINA_JAR: &str = "lib/catalina.jar";
+const SERVER_INFO: &str = "org/apache/catalina/util/ServerInfo.properties";
+
+pub fn java_version(java_home: &Path) -> Result<String, AppError> {
+    let java_exe = java_home.join(JAVA_BIN);
+    let output = Command::new(java_exe.as_path())
+        .arg("-version")
+        .output()
+        .map_err(|e| AppError::System(format!("Failed to run {}: {}", java_exe.display(), e)))?;
+    // java prints its version to stderr
+    let text = String::from_utf8_lossy(&output.stderr);
+    match parse_java_version(&text) {
+        Some(version) => Ok(version),
+        None => Err(AppError::System(format!(
+            "Failed to read java version from '{}'",
+            text.trim()
+        ))),
+    }
+}
+
+// JAVA_TOOL_OPTIONS and _JAVA_OPTIONS make java print "Picked up ..." before the version
+fn parse_java_version(text: &str) -> Option<String> {
+    text.lines()
+        .find(|t| t.contains("version \""))
+        .and_then(|t| t.split('"').nth(1))
+        .map(|t| t.to_string())
+}
+
+pub fn tomcat_version(tomcat_home: &Path) -> Result<String, AppError> {
+    let catalina_jar = tomcat_home.join(CATALINA_JAR);
+    let file = std::fs::File::open(catalina_jar.as_path()).map_err(|e| {
+        AppError::System(format!("Failed to open {}: {}", catalina_jar.display(), e))
+    })?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| {
+        AppError::System(format!("Failed to read {}: {}", catalina_jar.display(), e))
+    })?;
+    let mut content = String::new();
+    archive
+        .by_name(SERVER_INFO)
+        .map_err(|e| e.into())
+        .and_then(|mut entry| entry.read_to_string(&mut content))
+        .map_err(|e: std::io::Error| {
+            AppError::System(format!(
+                "Failed to read {} from {}: {}",
+                SERVER_INFO,
+                catalina_jar.display(),
+                e
+            ))
+        })?;
+
+    // server.info looks like "Apache Tomcat/9.0.80", server.number like "9.0.80.0"
+    let mut server_info = None;
+    let mut server_number = None;
+    for line in content.lines() {
+        if let Some((key, value)) = line.split_once('=') {
+            match key.trim() {
+                "server.info" => {
+                    server_info = value.trim().split_once('/').map(|t| t.1.to_string())
+                }
+                "server.number" => server_number = Some(value.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+    match server_info.or(server_number) {
+        Some(version) => Ok(version),
+        None => Err(AppError::System(format!(
+            "No version found in {}",
+            catalina_jar.display()
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+// comma separated constraints like "17", "11.0" or ">=11,<21", only the given
+// parts of a version are compared, so "17" matches every 17.x release
+#[derive(Debug, Clone)]
+pub struct VersionReq {
+    constraints: Vec<(Operator, Vec<u64>)>,
+}
+
+impl VersionReq {
+    pub fn parse(text: &str) -> Result<VersionReq, String> {
+        let mut constraints = Vec::new();
+        for constraint in text.split(',').map(|t| t.trim()) {
+            let (operator, version) = [
+                (">=", Operator::Ge),
+                ("<=", Operator::Le),
+                ("==", Operator::Eq),
+                (">", Operator::Gt),
+                ("<", Operator::Lt),
+                ("=", Operator::Eq),
+            ]
+            .iter()
+            .find_map(|(prefix, operator)| constraint.strip_prefix(prefix).map(|t| (*operator, t)))
+            .unwrap_or((Operator::Eq, constraint));
+            let parts = version_parts(version.trim());
+            if parts.is_empty() {
+                return Err(format!("invalid version constraint '{}'", text));
+            }
+            constraints.push((operator, parts));
+        }
+        Ok(VersionReq { constraints })
+    }
+
+    pub fn matches(&self, version: &str) -> bool {
+        let parts = version_parts(version);
+        self.constraints.iter().all(|(operator, required)| {
+            let actual: Vec<u64> = (0..required.len())
+                .map(|i| parts.get(i).copied().unwrap_or(0))
+                .collect();
+            let ordering = actual.cmp(required);
+            match operator {
+                Operator::Eq => ordering == Ordering::Equal,
+                Operator::Gt => ordering == Ordering::Greater,
+                Operator::Ge => ordering != Ordering::Less,
+                Operator::Lt => ordering == Ordering::Less,
+                Operator::Le => ordering != Ordering::Greater,
+            }
+        })
+    }
+}
+
+// numeric parts of a version, the legacy java scheme "1.8.0_392" becomes [8, 0, 392]
+pub fn version_parts(version: &str) -> Vec<u64> {
+    let mut parts = Vec::new();
+    for part in version.split(['.', '_', '+', '-']) {
+        match part.parse::<u64>() {
+            Ok(number) => parts.push(number),
+            Err(_) => break,
+        }
+    }
+    if parts.len() > 1 && parts[0] == 1 {
+        parts.remove(0);
+    }
+    parts
+}
+
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    version_parts(a).cmp(&version_parts(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parts_of_versions() {
+        assert_eq!(version_parts("17.0.8"), vec![17, 0, 8]);
+        assert_eq!(version_parts("1.8.0_392"), vec![8, 0, 392]);
+        assert_eq!(version_parts("21+35"), vec![21, 35]);
+        assert_eq!(version_parts("17-ea"), vec![17]);
+        assert_eq!(version_parts("1"), vec![1]);
+        assert!(version_parts("abc").is_empty());
+    }
+
+    #[test]
+    fn match_single_constraint() {
+        let req = VersionReq::parse("17").unwrap();
+        assert!(req.matches("17"));
+        assert!(req.matches("17.0.8"));
+        assert!(!req.matches("21.0.1"));
+        assert!(VersionReq::parse("1.8").unwrap().matches("1.8.0_392"));
+        assert!(VersionReq::parse("8").unwrap().matches("1.8.0_392"));
+        assert!(VersionReq::parse("9.0").unwrap().matches("9.0.80"));
+        assert!(!VersionReq::parse("9.0").unwrap().matches("9.1.0"));
+    }
+
+    #[test]
+    fn match_range() {
+        let req = VersionReq::parse(">=11,<21").unwrap();
+        assert!(req.matches("11"));
+        assert!(req.matches("17.0.8"));
+        assert!(!req.matches("21.0.1"));
+        assert!(!req.matches("1.8.0_392"));
+        let req = VersionReq::parse(">= 10.1, <= 10.1.15").unwrap();
+        assert!(req.matches("10.1"));
+        assert!(req.matches("10.1.15"));
+        assert!(!req.matches("10.1.16"));
+        // only the given parts are compared, 10.1.15 is not greater than 10.1
+        let req = VersionReq::parse(">10.1").unwrap();
+        assert!(!req.matches("10.1.15"));
+        assert!(req.matches("10.2.0"));
+    }
+
+    #[test]
+    fn parse_invalid_constraints() {
+        assert!(VersionReq::parse("abc").is_err());
+        assert!(VersionReq::parse(">=").is_err());
+        assert!(VersionReq::parse("11,").is_err());
+    }
+
+    #[test]
+    fn parse_java_version_output() {
+        let output = "openjdk version \"17.0.8\" 2023-07-18\nOpenJDK Runtime Environment\n";
+        assert_eq!(parse_java_version(output).unwrap(), "17.0.8");
+        let output = "Picked up JAVA_TOOL_OPTIONS: -Dfile.encoding=UTF-8\n\
+                      java version \"1.8.0_392\"\n";
+        assert_eq!(parse_java_version(output).unwrap(), "1.8.0_392");
+        assert!(parse_java_version("Error: could not find libjava.so").is_none());
+    }
+
+    #[test]
+    fn compare() {
+        assert_eq!(compare_versions("9.0.80", "10.1.15"), Ordering::Less);
+        assert_eq!(compare_versions("1.8.0_392", "11"), Ordering::Less);
+        assert_eq!(compare_versions("17.0.8", "17.0.8"), Ordering::Equal);
+    }
+}
@@ -1,34 +1,75 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
 #[derive(Debug, Clone)]
 pub struct TmsConfig {
     pub default: RuntimeConfig,
+    // named runtime profiles defined by [runtime.<name>]
+    pub runtimes: BTreeMap<String, RuntimeConfig>,
     pub projects: Vec<ProjectConfig>,
+    // projects defined in the tms.toml found from the current directory
+    pub local_projects: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct RuntimeConfig {
     pub java: Option<JavaConfig>,
     pub tomcat: Option<TomcatConfig>,
+    pub env: BTreeMap<String, VariableConfig>,
+    pub system_properties: BTreeMap<String, VariableConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub enum VariableConfig {
+    Value(String),
+    File(String),
 }
 
 #[derive(Debug, Clone)]
 pub struct JavaConfig {
     pub java_home: Option<String>,
-    pub java_options: Option<String>,
+    // version constraint used to pick a discovered JDK when java_home is not set
+    pub java_version: Option<String>,
+    pub java_options: Option<Vec<String>>,
+    pub java_options_prepend: Vec<String>,
+    pub java_options_append: Vec<String>,
 }
 
 #[derive(Debug, Clone)]
 pub struct TomcatConfig {
     pub tomcat_home: Option<String>,
+    // version constraint used to pick a Tomcat installed by `tms tomcat add`
+    pub tomcat_version: Option<String>,
     pub http_port: Option<u32>,
     pub server_port: Option<u32>,
     pub jpda_port: Option<u32>,
+    pub https_port: Option<u32>,
+    pub https_keystore: Option<String>,
+    pub https_keystore_password: Option<String>,
+    pub shutdown_timeout: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 pub struct ProjectConfig {
+    // config file that declares the project
+    pub source: PathBuf,
     pub name: String,
     pub alias: Option<String>,
     pub description: Option<String>,
+    pub runtime: Option<RuntimeConfig>,
+    pub runtime_profile: Option<String>,
+    // absolute paths or glob patterns of jars
+    pub shared_libs: Vec<String>,
+    pub conf_overlay: Option<String>,
+    pub server_xml_template: Option<String>,
+    pub context_xml_template: Option<String>,
+    pub items: Vec<ProjectItemConfig>,
+    // overlays selected with `--env`, items are already merged with the overlay
+    pub envs: BTreeMap<String, ProjectEnvConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProjectEnvConfig {
     pub runtime: Option<RuntimeConfig>,
     pub items: Vec<ProjectItemConfig>,
 }
@@ -39,4 +80,52 @@ pub struct ProjectItemConfig {
     pub alias: Option<String>,
     pub path: String,
     pub context_path: String,
+    pub context: ContextConfig,
+    pub env: BTreeMap<String, VariableConfig>,
+    pub system_properties: BTreeMap<String, VariableConfig>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ContextConfig {
+    pub reloadable: Option<bool>,
+    pub cross_context: Option<bool>,
+    pub cookies: Option<bool>,
+    pub resources: Vec<Vec<(String, String)>>,
+    pub environments: Vec<ContextEnvironmentConfig>,
+    pub parameters: Vec<ContextParameterConfig>,
+    pub web_resources: Option<WebResourcesConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContextEnvironmentConfig {
+    pub name: String,
+    pub value: String,
+    pub value_type: String,
+    pub override_value: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ContextParameterConfig {
+    pub name: String,
+    pub value: String,
+    pub override_value: Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WebResourcesConfig {
+    pub caching_allowed: Option<bool>,
+    pub cache_max_size: Option<u64>,
+    pub cache_ttl: Option<u64>,
+    pub pre: Vec<ResourceSetConfig>,
+    pub jar: Vec<ResourceSetConfig>,
+    pub post: Vec<ResourceSetConfig>,
+}
+
+#[derive(Debug, Clone)]
+pub struct ResourceSetConfig {
+    pub class_name: String,
+    pub base: String,
+    pub web_app_mount: Option<String>,
+    pub internal_path: Option<String>,
+    pub read_only: Option<bool>,
 }
@@ -0,0 +1,133 @@
+use crate::app::AppError;
+
+// expand a leading `~`, `${VAR}` and `${VAR:-default}`, `$${` is kept as `${`
+pub fn expand(field: &str, value: &str) -> Result<String, AppError> {
+    expand_with(field, value, |name| std::env::var(name).ok())
+}
+
+fn expand_with(
+    field: &str,
+    value: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<String, AppError> {
+    let value = expand_home(field, value)?;
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value.as_str();
+    while let Some(start) = rest.find("${") {
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start]);
+            result.push('{');
+            rest = &rest[start + 2..];
+            continue;
+        }
+        result.push_str(&rest[..start]);
+        let end = match rest[start..].find('}') {
+            Some(end) => start + end,
+            None => {
+                return Err(AppError::Config(format!(
+                    "Unclosed '${{' in {} '{}'",
+                    field, value
+                )))
+            }
+        };
+        let expression = &rest[start + 2..end];
+        let (name, default) = match expression.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expression, None),
+        };
+        if name.is_empty() {
+            return Err(AppError::Config(format!(
+                "Empty variable name in {} '{}'",
+                field, value
+            )));
+        }
+        match (lookup(name), default) {
+            (Some(variable), Some(default)) if variable.is_empty() => result.push_str(default),
+            (Some(variable), _) => result.push_str(&variable),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => {
+                return Err(AppError::Config(format!(
+                    "Environment variable '{}' used by {} is not set",
+                    name, field
+                )))
+            }
+        }
+        rest = &rest[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+pub fn expand_option(field: &str, value: Option<&String>) -> Result<Option<String>, AppError> {
+    value.map(|t| expand(field, t)).transpose()
+}
+
+fn expand_home(field: &str, value: &str) -> Result<String, AppError> {
+    let rest = match value.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', '\\']) => rest,
+        _ => return Ok(value.to_string()),
+    };
+    match dirs::home_dir() {
+        Some(home) => Ok(format!("{}{}", home.display(), rest)),
+        None => Err(AppError::Config(format!(
+            "Failed to expand '~' in {}: home directory not found",
+            field
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lookup(name: &str) -> Option<String> {
+        match name {
+            "VALUE" => Some("value".to_string()),
+            "EMPTY" => Some(String::new()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn expand_variables() {
+        assert_eq!(
+            expand_with("f", "a/${VALUE}/b", lookup).unwrap(),
+            "a/value/b"
+        );
+        assert_eq!(expand_with("f", "${VALUE:-x}", lookup).unwrap(), "value");
+        assert_eq!(expand_with("f", "${EMPTY:-x}", lookup).unwrap(), "x");
+        assert_eq!(expand_with("f", "${EMPTY:-}", lookup).unwrap(), "");
+        assert_eq!(expand_with("f", "${EMPTY}", lookup).unwrap(), "");
+        assert_eq!(expand_with("f", "${UNSET:-x}", lookup).unwrap(), "x");
+        assert_eq!(expand_with("f", "${UNSET:-}", lookup).unwrap(), "");
+    }
+
+    #[test]
+    fn expand_escaped() {
+        assert_eq!(
+            expand_with("f", "$${catalina.base}/logs", lookup).unwrap(),
+            "${catalina.base}/logs"
+        );
+        assert_eq!(expand_with("f", "a$b", lookup).unwrap(), "a$b");
+    }
+
+    #[test]
+    fn expand_errors() {
+        assert!(expand_with("f", "${UNSET}", lookup).is_err());
+        assert!(expand_with("f", "${VALUE", lookup).is_err());
+        assert!(expand_with("f", "${}", lookup).is_err());
+        assert!(expand_with("f", "${:-x}", lookup).is_err());
+    }
+
+    #[test]
+    fn expand_home_dir() {
+        let home = dirs::home_dir().unwrap().display().to_string();
+        assert_eq!(expand_with("f", "~", lookup).unwrap(), home);
+        assert_eq!(
+            expand_with("f", "~/x", lookup).unwrap(),
+            format!("{}/x", home)
+        );
+        assert_eq!(expand_with("f", "~user/x", lookup).unwrap(), "~user/x");
+        assert_eq!(expand_with("f", "a/~/x", lookup).unwrap(), "a/~/x");
+    }
+}
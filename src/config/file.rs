@@ -1,110 +1,627 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
-use serde::Deserialize;
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use toml::Spanned;
 
+use super::source::{KeySpans, SourceFile};
 use crate::app::AppError;
 
 const CONFIG_FILENAME: &str = "config.toml";
+const LOCAL_CONFIG_FILENAME: &str = "tms.toml";
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct TmsFileConfig {
     pub log_level: Option<String>,
-    pub include: Option<Vec<String>>,
+    pub include: Option<Vec<Spanned<String>>>,
+    pub include_optional: Option<Vec<Spanned<String>>>,
     pub default: Option<RuntimeFileConfig>,
+    #[serde(rename = "runtime")]
+    pub runtimes: Option<BTreeMap<String, RuntimeFileConfig>>,
     #[serde(rename = "project")]
-    pub projects: Option<Vec<ProjectFileConfig>>,
+    pub projects: Option<Vec<Spanned<ProjectFileConfig>>>,
+    #[serde(skip)]
+    pub local_projects: Vec<String>,
+    // where the keys of default and runtime are declared, as they are merged across files
+    #[serde(skip)]
+    pub key_spans: KeySpans,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 pub struct RuntimeFileConfig {
+    // named runtime profile, only used in project runtime
+    pub profile: Option<String>,
     pub java: Option<JavaFileConfig>,
     pub tomcat: Option<TomcatFileConfig>,
+    pub env: Option<BTreeMap<String, VariableFileConfig>>,
+    pub system_properties: Option<BTreeMap<String, VariableFileConfig>>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum VariableFileConfig {
+    File {
+        file: String,
+        // directory of the declaring file, a relative file is resolved against it
+        #[serde(skip)]
+        base_dir: PathBuf,
+    },
+    Value(toml::Value),
+}
+
+#[derive(Debug, Default, Deserialize)]
 pub struct JavaFileConfig {
+    // directory of the declaring file, relative java_home is resolved against it
+    #[serde(skip)]
+    pub base_dir: PathBuf,
     pub java_home: Option<String>,
-    pub java_options: Option<String>,
+    pub java_version: Option<String>,
+    pub java_options: Option<JavaOptionsFileConfig>,
+    pub java_options_prepend: Option<JavaOptionsFileConfig>,
+    pub java_options_append: Option<JavaOptionsFileConfig>,
 }
 
 #[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum JavaOptionsFileConfig {
+    Text(String),
+    List(Vec<String>),
+}
+
+#[derive(Debug, Default, Deserialize)]
 pub struct TomcatFileConfig {
+    #[serde(skip)]
+    pub base_dir: PathBuf,
     pub tomcat_home: Option<String>,
+    pub tomcat_version: Option<String>,
     pub http_port: Option<u32>,
     pub server_port: Option<u32>,
     pub jpda_port: Option<u32>,
+    pub https_port: Option<u32>,
+    // PKCS#12 keystore of the HTTPS connector, a self-signed one is generated when not set
+    pub https_keystore: Option<String>,
+    pub https_keystore_password: Option<String>,
+    pub shutdown_timeout: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ProjectFileConfig {
+    #[serde(skip)]
+    pub source: Rc<SourceFile>,
     pub name: Option<String>,
     pub alias: Option<String>,
     pub description: Option<String>,
-    pub runtime: Option<RuntimeFileConfig>,
+    // not spanned, toml cannot span tables that are only declared by sub tables
+    pub runtime: Option<ProjectRuntimeFileConfig>,
+    // jars linked into the lib directory of CATALINA_BASE
+    pub shared_libs: Option<Vec<String>>,
+    // directory layered over the conf directory of Tomcat
+    pub conf_overlay: Option<String>,
+    // rendered into conf instead of the copied file
+    pub server_xml_template: Option<String>,
+    pub context_xml_template: Option<String>,
 
     #[serde(rename = "item")]
-    pub items: Option<Vec<ProjectItemFileConfig>>,
+    pub items: Option<Vec<Spanned<ProjectItemFileConfig>>>,
+    #[serde(rename = "env")]
+    pub envs: Option<BTreeMap<String, ProjectEnvFileConfig>>,
 }
 
+// overlay of runtime and item settings selected with `--env`
 #[derive(Debug, Deserialize)]
+pub struct ProjectEnvFileConfig {
+    pub runtime: Option<RuntimeFileConfig>,
+    // keyed by item name
+    #[serde(rename = "item")]
+    pub items: Option<BTreeMap<String, ProjectItemFileConfig>>,
+}
+
+// `runtime = "<profile>"` or an inline `[project.runtime]` table
+#[derive(Debug)]
+pub enum ProjectRuntimeFileConfig {
+    Profile(String),
+    Inline(Box<RuntimeFileConfig>),
+}
+
+impl ProjectRuntimeFileConfig {
+    pub fn profile(&self) -> Option<&String> {
+        match self {
+            ProjectRuntimeFileConfig::Profile(profile) => Some(profile),
+            ProjectRuntimeFileConfig::Inline(runtime) => runtime.profile.as_ref(),
+        }
+    }
+
+    pub fn inline(&self) -> Option<&RuntimeFileConfig> {
+        match self {
+            ProjectRuntimeFileConfig::Profile(_) => None,
+            ProjectRuntimeFileConfig::Inline(runtime) => Some(runtime),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for ProjectRuntimeFileConfig {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct RuntimeVisitor;
+
+        impl<'de> Visitor<'de> for RuntimeVisitor {
+            type Value = ProjectRuntimeFileConfig;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                formatter.write_str("a runtime profile name or a runtime table")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(ProjectRuntimeFileConfig::Profile(value.to_string()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                let deserializer = de::value::MapAccessDeserializer::new(map);
+                let runtime = RuntimeFileConfig::deserialize(deserializer)?;
+                Ok(ProjectRuntimeFileConfig::Inline(Box::new(runtime)))
+            }
+        }
+
+        deserializer.deserialize_any(RuntimeVisitor)
+    }
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
 pub struct ProjectItemFileConfig {
     pub name: Option<String>,
     pub alias: Option<String>,
     pub path: Option<String>,
     #[serde(rename = "context-path")]
     pub context_path: Option<String>,
+    pub reloadable: Option<bool>,
+    #[serde(rename = "cross-context")]
+    pub cross_context: Option<bool>,
+    pub cookies: Option<bool>,
+    #[serde(rename = "resource")]
+    pub resources: Option<Vec<BTreeMap<String, toml::Value>>>,
+    #[serde(rename = "environment")]
+    pub environments: Option<Vec<ContextEnvironmentFileConfig>>,
+    #[serde(rename = "parameter")]
+    pub parameters: Option<Vec<ContextParameterFileConfig>>,
+    #[serde(rename = "web-resources")]
+    pub web_resources: Option<WebResourcesFileConfig>,
+    pub env: Option<BTreeMap<String, VariableFileConfig>>,
+    #[serde(rename = "system-properties", alias = "system_properties")]
+    pub system_properties: Option<BTreeMap<String, VariableFileConfig>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContextEnvironmentFileConfig {
+    pub name: Option<String>,
+    pub value: Option<toml::Value>,
+    #[serde(rename = "type")]
+    pub value_type: Option<String>,
+    #[serde(rename = "override")]
+    pub override_value: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ContextParameterFileConfig {
+    pub name: Option<String>,
+    pub value: Option<toml::Value>,
+    #[serde(rename = "override")]
+    pub override_value: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WebResourcesFileConfig {
+    #[serde(rename = "caching-allowed")]
+    pub caching_allowed: Option<bool>,
+    #[serde(rename = "cache-max-size")]
+    pub cache_max_size: Option<u64>,
+    #[serde(rename = "cache-ttl")]
+    pub cache_ttl: Option<u64>,
+    pub pre: Option<Vec<ResourceSetFileConfig>>,
+    pub jar: Option<Vec<ResourceSetFileConfig>>,
+    pub post: Option<Vec<ResourceSetFileConfig>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ResourceSetFileConfig {
+    #[serde(rename = "class-name")]
+    pub class_name: Option<String>,
+    pub base: Option<String>,
+    #[serde(rename = "web-app-mount")]
+    pub web_app_mount: Option<String>,
+    #[serde(rename = "internal-path")]
+    pub internal_path: Option<String>,
+    #[serde(rename = "read-only")]
+    pub read_only: Option<bool>,
 }
 
 pub fn load_config_file(config_path: Option<PathBuf>) -> Result<TmsFileConfig, AppError> {
-    let (base_dir, config_file) = match config_path {
-        Some(path) => {
-            let dir = path.parent().map(|p| p.to_path_buf()).unwrap_or_else(super::get_config_dir);
-            (dir, path)
-        }
-        None => {
-            let dir = super::get_config_dir();
-            (dir.clone(), dir.join(CONFIG_FILENAME))
-        }
+    let explicit = config_path.is_some();
+    let config_file = match config_path {
+        Some(path) => path,
+        None => super::get_config_dir().join(CONFIG_FILENAME),
     };
+    let local_file = find_local_config();
 
-    if !config_file.exists() {
+    let mut loader = ConfigLoader::default();
+    let mut config = if config_file.exists() {
+        loader.load(config_file.as_path())?
+    } else if !explicit && local_file.is_some() {
+        TmsFileConfig::default()
+    } else {
         return Err(AppError::Config(format!(
             "config file not found: {}",
             config_file.display()
         )));
-    }
-    let file_content = std::fs::read_to_string(config_file.as_path())
-        .map_err(|e| AppError::Config(format!("Failed to read config file: {}", e)))?;
+    };
 
-    let mut config = toml::from_str::<TmsFileConfig>(file_content.as_str())
-        .map_err(|e| AppError::Config(format!("Failed to parse config file: {}", e)))?;
+    // the project-local config is merged on top of the user-level config,
+    // its projects replace user-level projects with the same name
+    let is_same_file = |a: &Path, b: &Path| match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    };
+    match local_file {
+        Some(local_file) if is_same_file(&local_file, &config_file) => {
+            config.local_projects = project_names(&config);
+        }
+        // included by the user-level config, so its projects are merged already,
+        // it is loaded again on its own only to tell them apart
+        Some(local_file) if loader.is_loaded(local_file.as_path()) => {
+            log::debug!("Local config file {} is included", local_file.display());
+            let local_config = ConfigLoader::default().load(local_file.as_path())?;
+            config.local_projects = project_names(&local_config);
+        }
+        Some(local_file) => {
+            log::debug!("Found local config file {}", local_file.display());
+            let local_config = loader.load(local_file.as_path())?;
+            let local_projects = project_names(&local_config);
+            if let Some(projects) = config.projects.as_mut() {
+                projects.retain(|t| match &t.get_ref().name {
+                    Some(name) if local_projects.contains(name) => {
+                        log::debug!("Project '{}' is overridden by {}", name, local_file.display());
+                        false
+                    }
+                    _ => true,
+                });
+            }
+            config = merge_file_config(local_config, config);
+            config.local_projects = local_projects;
+        }
+        None => {}
+    }
 
     if config.projects.is_none() {
         config.projects = Some(Vec::new());
     }
-    if let Some(includes) = &config.include {
-        let projects = config.projects.as_mut().unwrap();
-        for include in includes {
-            let include_file = base_dir.join(include);
-            if !include_file.exists() {
-                return Err(AppError::Config(format!(
-                    "include file not found: {}",
-                    include_file.display()
-                )));
+    Ok(config)
+}
+
+fn project_names(config: &TmsFileConfig) -> Vec<String> {
+    config
+        .projects
+        .iter()
+        .flatten()
+        .filter_map(|t| t.get_ref().name.clone())
+        .collect()
+}
+
+// look for tms.toml in the current directory and its parents
+fn find_local_config() -> Option<PathBuf> {
+    let current_dir = std::env::current_dir().ok()?;
+    current_dir
+        .ancestors()
+        .map(|dir| dir.join(LOCAL_CONFIG_FILENAME))
+        .find(|file| file.is_file())
+}
+
+#[derive(Default)]
+struct ConfigLoader {
+    // files being loaded, used to detect include cycles
+    stack: Vec<PathBuf>,
+    loaded: HashSet<PathBuf>,
+}
+
+impl ConfigLoader {
+    fn is_loaded(&self, path: &Path) -> bool {
+        path.canonicalize().is_ok_and(|t| self.loaded.contains(&t))
+    }
+
+    // values of a file take precedence over the files it includes,
+    // and a later include takes precedence over an earlier one
+    fn load(&mut self, path: &Path) -> Result<TmsFileConfig, AppError> {
+        let path = path.canonicalize().map_err(|e| {
+            AppError::Config(format!("Failed to read config file {}: {}", path.display(), e))
+        })?;
+        if !self.loaded.insert(path.clone()) {
+            log::debug!("Config file {} is already loaded, skipped", path.display());
+            return Ok(TmsFileConfig::default());
+        }
+
+        let (source, mut config) = parse_config_file(path.clone())?;
+        config.key_spans = KeySpans::collect(&source, &["default", "runtime"]);
+        let base_dir = source.path.parent().map(|p| p.to_path_buf()).unwrap_or_default();
+        set_base_dir(config.default.as_mut(), &base_dir);
+        for runtime in config.runtimes.iter_mut().flat_map(|t| t.values_mut()) {
+            set_base_dir(Some(runtime), &base_dir);
+        }
+        for project in config.projects.iter_mut().flatten() {
+            let project = project.get_mut();
+            project.source = source.clone();
+            if let Some(ProjectRuntimeFileConfig::Inline(runtime)) = project.runtime.as_mut() {
+                set_base_dir(Some(runtime.as_mut()), &base_dir);
+            }
+            for item in project.items.iter_mut().flatten() {
+                set_item_base_dir(item.get_mut(), &base_dir);
             }
-            let file_content = std::fs::read_to_string(include_file.as_path())
-                .map_err(|e| AppError::Config(format!("Failed to read include file: {}", e)))?;
+            for env in project.envs.iter_mut().flat_map(|t| t.values_mut()) {
+                set_base_dir(env.runtime.as_mut(), &base_dir);
+                for item in env.items.iter_mut().flat_map(|t| t.values_mut()) {
+                    set_item_base_dir(item, &base_dir);
+                }
+            }
+        }
+
+        self.stack.push(path);
+        let mut included = TmsFileConfig::default();
+        let includes = config.include.iter().flatten().map(|t| (t, false));
+        let optional_includes = config.include_optional.iter().flatten().map(|t| (t, true));
+        for (include, optional) in includes.chain(optional_includes) {
+            let files = resolve_include(&source, &base_dir, include, optional)?;
+            for file in files {
+                self.check_cycle(&source, include.span(), file.as_path())?;
+                let include_config = self.load(file.as_path())?;
+                included = merge_file_config(include_config, included);
+            }
+        }
+        self.stack.pop();
+
+        Ok(merge_file_config(config, included))
+    }
+
+    fn check_cycle(
+        &self,
+        source: &SourceFile,
+        span: Range<usize>,
+        file: &Path,
+    ) -> Result<(), AppError> {
+        let file = match file.canonicalize() {
+            Ok(file) => file,
+            Err(_) => return Ok(()),
+        };
+        if let Some(start) = self.stack.iter().position(|t| *t == file) {
+            let mut chain: Vec<String> = self.stack[start..]
+                .iter()
+                .map(|t| t.display().to_string())
+                .collect();
+            chain.push(file.display().to_string());
+            return Err(source.error(
+                Some(span),
+                format!("Include cycle detected: {}", chain.join(" -> ")),
+            ));
+        }
+        Ok(())
+    }
+}
 
-            let include_config = toml::from_str::<TmsFileConfig>(file_content.as_str())
-                .map_err(|e| AppError::Config(format!("Failed to parse include file: {}", e)))?;
+fn set_base_dir(runtime: Option<&mut RuntimeFileConfig>, base_dir: &Path) {
+    if let Some(runtime) = runtime {
+        if let Some(java) = runtime.java.as_mut() {
+            java.base_dir = base_dir.to_path_buf();
+        }
+        if let Some(tomcat) = runtime.tomcat.as_mut() {
+            tomcat.base_dir = base_dir.to_path_buf();
+        }
+        set_variables_base_dir(runtime.env.as_mut(), base_dir);
+        set_variables_base_dir(runtime.system_properties.as_mut(), base_dir);
+    }
+}
+
+fn set_item_base_dir(item: &mut ProjectItemFileConfig, base_dir: &Path) {
+    set_variables_base_dir(item.env.as_mut(), base_dir);
+    set_variables_base_dir(item.system_properties.as_mut(), base_dir);
+}
+
+fn set_variables_base_dir(
+    variables: Option<&mut BTreeMap<String, VariableFileConfig>>,
+    base_dir: &Path,
+) {
+    for variable in variables.into_iter().flat_map(|t| t.values_mut()) {
+        if let VariableFileConfig::File { base_dir: dir, .. } = variable {
+            *dir = base_dir.to_path_buf();
+        }
+    }
+}
 
-            if let Some(include_projects) = include_config.projects {
-                for project_config in include_projects {
-                    projects.push(project_config);
+fn resolve_include(
+    source: &SourceFile,
+    base_dir: &Path,
+    include: &Spanned<String>,
+    optional: bool,
+) -> Result<Vec<PathBuf>, AppError> {
+    let pattern = base_dir.join(include.get_ref());
+    let is_glob = include.get_ref().contains(['*', '?', '[']);
+    let files = if is_glob {
+        let paths = glob::glob(pattern.to_string_lossy().as_ref()).map_err(|e| {
+            source.error(Some(include.span()), format!("Invalid include pattern: {}", e))
+        })?;
+        let mut files = Vec::new();
+        for path in paths {
+            let path = path.map_err(|e| {
+                source.error(Some(include.span()), format!("Failed to read include: {}", e))
+            })?;
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+        files.sort();
+        files
+    } else if pattern.exists() {
+        vec![pattern.clone()]
+    } else {
+        Vec::new()
+    };
+
+    if files.is_empty() {
+        if optional {
+            log::debug!("Optional include '{}' matches no file", include.get_ref());
+        } else {
+            return Err(source.error(
+                Some(include.span()),
+                format!("include file not found: {}", pattern.display()),
+            ));
+        }
+    }
+    Ok(files)
+}
+
+fn merge_file_config(high: TmsFileConfig, low: TmsFileConfig) -> TmsFileConfig {
+    let projects = match (high.projects, low.projects) {
+        (Some(mut high), Some(low)) => {
+            high.extend(low);
+            Some(high)
+        }
+        (high, low) => high.or(low),
+    };
+    TmsFileConfig {
+        log_level: high.log_level.or(low.log_level),
+        include: None,
+        include_optional: None,
+        default: match (high.default, low.default) {
+            (Some(high), Some(low)) => Some(merge_runtime(high, low)),
+            (high, low) => high.or(low),
+        },
+        runtimes: match (high.runtimes, low.runtimes) {
+            (Some(high), Some(mut low)) => {
+                for (name, runtime) in high {
+                    let runtime = match low.remove(&name) {
+                        Some(previous) => merge_runtime(runtime, previous),
+                        None => runtime,
+                    };
+                    low.insert(name, runtime);
                 }
+                Some(low)
             }
+            (high, low) => high.or(low),
+        },
+        projects,
+        local_projects: Vec::new(),
+        key_spans: low.key_spans.merge(high.key_spans),
+    }
+}
+
+fn merge_runtime(high: RuntimeFileConfig, low: RuntimeFileConfig) -> RuntimeFileConfig {
+    let java = match (high.java, low.java) {
+        (Some(high), Some(low)) => Some(JavaFileConfig {
+            base_dir: if high.java_home.is_some() {
+                high.base_dir
+            } else {
+                low.base_dir
+            },
+            java_home: high.java_home.or(low.java_home),
+            java_version: high.java_version.or(low.java_version),
+            java_options: high.java_options.or(low.java_options),
+            java_options_prepend: high.java_options_prepend.or(low.java_options_prepend),
+            java_options_append: high.java_options_append.or(low.java_options_append),
+        }),
+        (high, low) => high.or(low),
+    };
+    let tomcat = match (high.tomcat, low.tomcat) {
+        (Some(high), Some(low)) => Some(TomcatFileConfig {
+            base_dir: if high.tomcat_home.is_some() {
+                high.base_dir
+            } else {
+                low.base_dir
+            },
+            tomcat_home: high.tomcat_home.or(low.tomcat_home),
+            tomcat_version: high.tomcat_version.or(low.tomcat_version),
+            http_port: high.http_port.or(low.http_port),
+            server_port: high.server_port.or(low.server_port),
+            jpda_port: high.jpda_port.or(low.jpda_port),
+            https_port: high.https_port.or(low.https_port),
+            https_keystore: high.https_keystore.or(low.https_keystore),
+            https_keystore_password: high
+                .https_keystore_password
+                .or(low.https_keystore_password),
+            shutdown_timeout: high.shutdown_timeout.or(low.shutdown_timeout),
+        }),
+        (high, low) => high.or(low),
+    };
+    RuntimeFileConfig {
+        profile: high.profile.or(low.profile),
+        java,
+        tomcat,
+        env: merge_variables(high.env, low.env),
+        system_properties: merge_variables(high.system_properties, low.system_properties),
+    }
+}
+
+// settings of the overlay replace those of the item, named entries are replaced by name
+pub fn merge_item(
+    item: &ProjectItemFileConfig,
+    overlay: &ProjectItemFileConfig,
+) -> ProjectItemFileConfig {
+    let item = item.clone();
+    let overlay = overlay.clone();
+    ProjectItemFileConfig {
+        name: item.name,
+        alias: item.alias,
+        path: overlay.path.or(item.path),
+        context_path: overlay.context_path.or(item.context_path),
+        reloadable: overlay.reloadable.or(item.reloadable),
+        cross_context: overlay.cross_context.or(item.cross_context),
+        cookies: overlay.cookies.or(item.cookies),
+        resources: merge_named(item.resources, overlay.resources, |t| {
+            t.get("name").and_then(|t| t.as_str()).map(|t| t.to_string())
+        }),
+        environments: merge_named(item.environments, overlay.environments, |t| t.name.clone()),
+        parameters: merge_named(item.parameters, overlay.parameters, |t| t.name.clone()),
+        web_resources: overlay.web_resources.or(item.web_resources),
+        env: merge_variables(overlay.env, item.env),
+        system_properties: merge_variables(overlay.system_properties, item.system_properties),
+    }
+}
+
+fn merge_named<T, F>(low: Option<Vec<T>>, high: Option<Vec<T>>, name: F) -> Option<Vec<T>>
+where
+    F: Fn(&T) -> Option<String>,
+{
+    match (low, high) {
+        (Some(mut low), Some(high)) => {
+            for value in high {
+                let key = name(&value);
+                match low.iter().position(|t| key.is_some() && name(t) == key) {
+                    Some(i) => low[i] = value,
+                    None => low.push(value),
+                }
+            }
+            Some(low)
         }
+        (low, high) => high.or(low),
     }
+}
 
-    Ok(config)
+fn merge_variables(
+    high: Option<BTreeMap<String, VariableFileConfig>>,
+    low: Option<BTreeMap<String, VariableFileConfig>>,
+) -> Option<BTreeMap<String, VariableFileConfig>> {
+    match (high, low) {
+        (Some(high), Some(mut low)) => {
+            low.extend(high);
+            Some(low)
+        }
+        (high, low) => high.or(low),
+    }
+}
+
+fn parse_config_file(path: PathBuf) -> Result<(Rc<SourceFile>, TmsFileConfig), AppError> {
+    let content = std::fs::read_to_string(path.as_path()).map_err(|e| {
+        AppError::Config(format!("Failed to read config file {}: {}", path.display(), e))
+    })?;
+    let source = Rc::new(SourceFile::new(path, content));
+    let config = toml::from_str::<TmsFileConfig>(source.content.as_str()).map_err(|e| {
+        source.error(e.span(), format!("Failed to parse config file: {}", e.message()))
+    })?;
+    Ok((source, config))
 }
@@ -1,24 +1,47 @@
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use crate::app;
 use log::LevelFilter;
 
 use crate::config::domain::{
-    JavaConfig, ProjectConfig, ProjectItemConfig, RuntimeConfig, TmsConfig, TomcatConfig,
+    ContextConfig, ContextEnvironmentConfig, ContextParameterConfig, JavaConfig, ProjectConfig,
+    ProjectEnvConfig,
+    ProjectItemConfig, ResourceSetConfig, RuntimeConfig, TmsConfig, TomcatConfig,
+    VariableConfig, WebResourcesConfig,
 };
 use crate::config::file;
+use crate::config::file::merge_item;
 use crate::config::file::{
-    JavaFileConfig, ProjectFileConfig, ProjectItemFileConfig, RuntimeFileConfig, TomcatFileConfig,
+    JavaFileConfig, JavaOptionsFileConfig, ProjectFileConfig, ProjectItemFileConfig, ResourceSetFileConfig,
+    RuntimeFileConfig, TomcatFileConfig, VariableFileConfig,
 };
+use crate::config::expand::{expand, expand_option};
+use crate::config::source::{KeySpans, SourceFile};
 use crate::app::AppError;
+use toml::Spanned;
+
+const WEB_RESOURCES_PACKAGE: &str = "org.apache.catalina.webresources";
 
 pub fn init(config_path: Option<PathBuf>) -> Result<TmsConfig, AppError> {
     let file_config = file::load_config_file(config_path)?;
     init_logger(file_config.log_level.as_ref())?;
+    let key_spans = &file_config.key_spans;
+    if file_config.default.as_ref().is_some_and(|t| t.profile.is_some()) {
+        return Err(key_spans.locate(
+            &["default", "profile"],
+            AppError::Config("profile is only supported in project runtime".to_string()),
+        ));
+    }
+    let runtimes = init_runtime_profiles(file_config.runtimes.as_ref(), key_spans)?;
+    let locate_default = |keys: &[&str], e| key_spans.locate(&[&["default"], keys].concat(), e);
     let config = TmsConfig {
-        default: init_runtime(file_config.default.as_ref(), true)?,
-        projects: init_projects(file_config.projects.as_ref())?,
+        default: init_runtime(file_config.default.as_ref(), true, &locate_default)?,
+        projects: init_projects(file_config.projects.as_ref(), &runtimes)?,
+        runtimes,
+        local_projects: file_config.local_projects,
     };
 
     Ok(config)
@@ -46,29 +69,99 @@ fn init_logger(level_text: Option<&String>) -> Result<(), AppError> {
     }
 }
 
-fn init_runtime(runtime_config: Option<&RuntimeFileConfig>, is_default: bool) -> Result<RuntimeConfig, AppError> {
+// attaches the location of a key, given relative to the runtime table, to an error
+type Locate<'a> = &'a dyn Fn(&[&str], AppError) -> AppError;
+
+fn init_runtime(
+    runtime_config: Option<&RuntimeFileConfig>,
+    is_default: bool,
+    locate: Locate,
+) -> Result<RuntimeConfig, AppError> {
     let runtime_config = match runtime_config {
         Some(runtime_config) => RuntimeConfig {
-            java: Some(init_java_config(runtime_config.java.as_ref(), is_default)?),
-            tomcat: Some(init_tomcat_config(runtime_config.tomcat.as_ref(), is_default)?),
+            java: Some(init_java_config(runtime_config.java.as_ref(), is_default, locate)?),
+            tomcat: Some(init_tomcat_config(runtime_config.tomcat.as_ref(), is_default, locate)?),
+            env: init_variables("env", runtime_config.env.as_ref(), locate)?,
+            system_properties: init_variables(
+                "system_properties",
+                runtime_config.system_properties.as_ref(),
+                locate,
+            )?,
         },
         None => RuntimeConfig {
-            java: Some(init_java_config(None, is_default)?),
-            tomcat: Some(init_tomcat_config(None, is_default)?),
+            java: Some(init_java_config(None, is_default, locate)?),
+            tomcat: Some(init_tomcat_config(None, is_default, locate)?),
+            env: BTreeMap::new(),
+            system_properties: BTreeMap::new(),
         },
     };
     Ok(runtime_config)
 }
 
-fn init_java_config(java_config: Option<&JavaFileConfig>, is_default: bool) -> Result<JavaConfig, AppError> {
+fn init_runtime_profiles(
+    runtimes: Option<&BTreeMap<String, RuntimeFileConfig>>,
+    key_spans: &KeySpans,
+) -> Result<BTreeMap<String, RuntimeConfig>, AppError> {
+    let mut configs = BTreeMap::new();
+    for (name, runtime) in runtimes.into_iter().flatten() {
+        let locate = |keys: &[&str], e| {
+            key_spans.locate(&[&["runtime", name.as_str()], keys].concat(), e)
+        };
+        if runtime.profile.is_some() {
+            return Err(locate(
+                &["profile"],
+                AppError::Config(format!(
+                    "profile is only supported in project runtime, found in runtime profile '{}'",
+                    name
+                )),
+            ));
+        }
+        let config = init_runtime(Some(runtime), false, &locate).map_err(|e| match e {
+            AppError::Config(message) => {
+                AppError::Config(format!("{} (runtime profile '{}')", message, name))
+            }
+            e => e,
+        })?;
+        configs.insert(name.clone(), config);
+    }
+    Ok(configs)
+}
+
+fn init_java_config(
+    java_config: Option<&JavaFileConfig>,
+    is_default: bool,
+    locate: Locate,
+) -> Result<JavaConfig, AppError> {
     let mut java_home = None;
+    let mut java_version = None;
     let mut java_opts = None;
+    let mut java_opts_prepend = Vec::new();
+    let mut java_opts_append = Vec::new();
     if let Some(value) = java_config {
-        java_home.clone_from(&value.java_home);
-        java_opts.clone_from(&value.java_options);
+        java_home = expand_option("java_home", value.java_home.as_ref())
+            .map_err(|e| locate(&["java", "java_home"], e))?
+            .map(|t| resolve_path(&value.base_dir, t));
+        if let Some(version) = &value.java_version {
+            app::version::VersionReq::parse(version)
+                .map_err(|e| AppError::Config(format!("Invalid java_version: {}", e)))
+                .map_err(|e| locate(&["java", "java_version"], e))?;
+            java_version = Some(version.clone());
+        }
+        if let Some(options) = &value.java_options {
+            java_opts = Some(init_java_options("java_options", options)
+                .map_err(|e| locate(&["java", "java_options"], e))?);
+        }
+        if let Some(options) = &value.java_options_prepend {
+            java_opts_prepend = init_java_options("java_options_prepend", options)
+                .map_err(|e| locate(&["java", "java_options_prepend"], e))?;
+        }
+        if let Some(options) = &value.java_options_append {
+            java_opts_append = init_java_options("java_options_append", options)
+                .map_err(|e| locate(&["java", "java_options_append"], e))?;
+        }
     }
 
-    if java_home.is_none() && is_default {
+    if java_home.is_none() && java_version.is_none() && is_default {
         log::trace!("No java_home set, falling back to environment variable JAVA_HOME");
         java_home = match std::env::var("JAVA_HOME") {
             Ok(value) => Some(value),
@@ -81,25 +174,69 @@ fn init_java_config(java_config: Option<&JavaFileConfig>, is_default: bool) -> R
 
     let java_config = JavaConfig {
         java_home,
+        java_version,
         java_options: java_opts,
+        java_options_prepend: java_opts_prepend,
+        java_options_append: java_opts_append,
     };
     Ok(java_config)
 }
 
-fn init_tomcat_config(tomcat_config: Option<&TomcatFileConfig>, is_default: bool) -> Result<TomcatConfig, AppError> {
+fn init_java_options(name: &str, options: &JavaOptionsFileConfig) -> Result<Vec<String>, AppError> {
+    let options = match options {
+        JavaOptionsFileConfig::List(options) => options.clone(),
+        JavaOptionsFileConfig::Text(text) => match shlex::split(text) {
+            Some(options) => options,
+            None => {
+                return Err(AppError::Config(format!(
+                    "Invalid {} '{}': unbalanced quotes",
+                    name, text
+                )))
+            }
+        },
+    };
+    options.iter().map(|t| expand(name, t)).collect()
+}
+
+fn init_tomcat_config(
+    tomcat_config: Option<&TomcatFileConfig>,
+    is_default: bool,
+    locate: Locate,
+) -> Result<TomcatConfig, AppError> {
     let mut tomcat_home = None;
+    let mut tomcat_version = None;
     let mut http_port = None;
     let mut server_port = None;
     let mut jpda_port = None;
+    let mut https_port = None;
+    let mut https_keystore = None;
+    let mut https_keystore_password = None;
+    let mut shutdown_timeout = None;
 
     if let Some(value) = tomcat_config {
-        tomcat_home.clone_from(&value.tomcat_home);
+        tomcat_home = expand_option("tomcat_home", value.tomcat_home.as_ref())
+            .map_err(|e| locate(&["tomcat", "tomcat_home"], e))?
+            .map(|t| resolve_path(&value.base_dir, t));
+        if let Some(version) = &value.tomcat_version {
+            app::version::VersionReq::parse(version)
+                .map_err(|e| AppError::Config(format!("Invalid tomcat_version: {}", e)))
+                .map_err(|e| locate(&["tomcat", "tomcat_version"], e))?;
+            tomcat_version = Some(version.clone());
+        }
         http_port = value.http_port;
         server_port = value.server_port;
         jpda_port = value.jpda_port;
+        https_port = value.https_port;
+        https_keystore = expand_option("https_keystore", value.https_keystore.as_ref())
+            .map_err(|e| locate(&["tomcat", "https_keystore"], e))?
+            .map(|t| resolve_path(&value.base_dir, t));
+        https_keystore_password =
+            expand_option("https_keystore_password", value.https_keystore_password.as_ref())
+                .map_err(|e| locate(&["tomcat", "https_keystore_password"], e))?;
+        shutdown_timeout = value.shutdown_timeout;
     }
 
-    if tomcat_home.is_none() && is_default {
+    if tomcat_home.is_none() && tomcat_version.is_none() && is_default {
         log::trace!("No tomcat_home set, falling back to environment variable CATALINA_HOME");
         tomcat_home = match std::env::var("CATALINA_HOME") {
             Ok(value) => Some(value),
@@ -110,56 +247,112 @@ fn init_tomcat_config(tomcat_config: Option<&TomcatFileConfig>, is_default: bool
         };
     }
 
-    if http_port.is_none() {
+    if http_port.is_none() && is_default {
         log::trace!("No http_port set, falling back to 8080");
         http_port = Some(8080);
     }
-    if server_port.is_none() {
+    if server_port.is_none() && is_default {
         log::trace!("No server_port set, falling back to 8005");
         server_port = Some(8005);
     }
 
-    if jpda_port.is_none() {
+    if jpda_port.is_none() && is_default {
         log::trace!("No jpda_port set, falling back to 8000");
         jpda_port = Some(8000);
     }
 
+    if shutdown_timeout.is_none() && is_default {
+        log::trace!("No shutdown_timeout set, falling back to 10 seconds");
+        shutdown_timeout = Some(10);
+    }
+
     let tomcat_config = TomcatConfig {
         tomcat_home,
+        tomcat_version,
         http_port,
         server_port,
         jpda_port,
+        https_port,
+        https_keystore,
+        https_keystore_password,
+        shutdown_timeout,
     };
     Ok(tomcat_config)
 }
 
 fn init_projects(
-    projects: Option<&Vec<ProjectFileConfig>>,
+    projects: Option<&Vec<Spanned<ProjectFileConfig>>>,
+    runtimes: &BTreeMap<String, RuntimeConfig>,
 ) -> Result<Vec<ProjectConfig>, AppError> {
     if projects.is_none() {
         return Err(AppError::Config("No project defined".to_string()));
     }
 
     let mut projects_config = Vec::new();
-    for (i, project) in projects.unwrap().iter().enumerate() {
+    let mut project_names = UniqueNames::new("project name or alias");
+    for project in projects.unwrap() {
+        let span = project.span();
+        let project = project.get_ref();
+        let source = project.source.as_ref();
         if project.name.is_none() {
-            return Err(AppError::Config(format!(
-                "No name defined for project {}",
-                i + 1
-            )));
+            return Err(source.error(Some(span), "No name defined for project"));
         }
         let name = project.name.as_ref().unwrap().clone();
-        let runtime = match project.runtime.as_ref() {
+        let project_runtime = project.runtime.as_ref();
+        let runtime = match project_runtime.and_then(|t| t.inline()) {
             None => None,
-            Some(runtime) => Some(init_runtime(Some(runtime), false)?),
+            Some(runtime) => Some(init_runtime(Some(runtime), false, &|_, e| {
+                source.locate(span.clone(), e)
+            })?),
         };
-        let items = init_project_items(&name, project.items.as_ref())?;
+        let runtime_profile = project_runtime.and_then(|t| t.profile()).cloned();
+        if let Some(profile) = runtime_profile.as_ref() {
+            if !runtimes.contains_key(profile) {
+                return Err(source.error(
+                    Some(span.clone()),
+                    format!("Runtime profile '{}' is not defined", profile),
+                ));
+            }
+        }
+        let owner = format!("name of project '{}'", name);
+        project_names.insert(&name, owner, source, &span)?;
+        if let Some(alias) = project.alias.as_ref().filter(|t| **t != name) {
+            let owner = format!("alias of project '{}'", name);
+            project_names.insert(alias, owner, source, &span)?;
+        }
+
+        let base_dir = source.path.parent().unwrap_or(Path::new(""));
+        let mut shared_libs = Vec::new();
+        for shared_lib in project.shared_libs.iter().flatten() {
+            let shared_lib =
+                expand("shared_libs", shared_lib).map_err(|e| source.locate(span.clone(), e))?;
+            shared_libs.push(resolve_path(base_dir, shared_lib));
+        }
+        let project_path = |field: &str, value: Option<&String>| -> Result<_, AppError> {
+            let value = expand_option(field, value).map_err(|e| source.locate(span.clone(), e))?;
+            Ok(value.map(|t| resolve_path(base_dir, t)))
+        };
+        let conf_overlay = project_path("conf_overlay", project.conf_overlay.as_ref())?;
+        let server_xml_template =
+            project_path("server_xml_template", project.server_xml_template.as_ref())?;
+        let context_xml_template =
+            project_path("context_xml_template", project.context_xml_template.as_ref())?;
+        let items = init_project_items(&name, source, span.clone(), project.items.as_ref())?;
+        let envs = init_project_envs(&name, project, span.clone())?;
         let project_config = ProjectConfig {
+            source: source.path.clone(),
             name,
             alias: project.alias.clone(),
-            description: project.description.clone(),
+            description: expand_option("description", project.description.as_ref())
+                .map_err(|e| source.locate(span.clone(), e))?,
             runtime,
+            runtime_profile,
+            shared_libs,
+            conf_overlay,
+            server_xml_template,
+            context_xml_template,
             items,
+            envs,
         };
         projects_config.push(project_config);
     }
@@ -167,63 +360,413 @@ fn init_projects(
     Ok(projects_config)
 }
 
+fn init_project_envs(
+    project_name: &str,
+    project: &ProjectFileConfig,
+    span: Range<usize>,
+) -> Result<BTreeMap<String, ProjectEnvConfig>, AppError> {
+    let source = project.source.as_ref();
+    let items = project.items.iter().flatten();
+    let mut envs = BTreeMap::new();
+    for (env_name, env) in project.envs.iter().flatten() {
+        let runtime = match env.runtime.as_ref() {
+            None => None,
+            Some(runtime) if runtime.profile.is_some() => {
+                return Err(source.error(
+                    Some(span),
+                    format!("profile is not supported in env '{}'", env_name),
+                ))
+            }
+            Some(runtime) => Some(init_runtime(Some(runtime), false, &|_, e| {
+                source.locate(span.clone(), e)
+            })?),
+        };
+
+        let overlays = env.items.as_ref();
+        for item_name in overlays.into_iter().flat_map(|t| t.keys()) {
+            let exists = items.clone().any(|t| t.get_ref().name.as_ref() == Some(item_name));
+            if !exists {
+                return Err(source.error(
+                    Some(span),
+                    format!(
+                        "Item '{}' of env '{}' is not defined in project '{}'",
+                        item_name, env_name, project_name
+                    ),
+                ));
+            }
+        }
+        let env_items: Vec<Spanned<ProjectItemFileConfig>> = items
+            .clone()
+            .map(|item| {
+                let overlay = item.get_ref().name.as_ref().and_then(|t| overlays?.get(t));
+                match overlay {
+                    Some(overlay) => Spanned::new(span.clone(), merge_item(item.get_ref(), overlay)),
+                    None => item.clone(),
+                }
+            })
+            .collect();
+        let items = init_project_items(project_name, source, span.clone(), Some(&env_items))?;
+        envs.insert(env_name.clone(), ProjectEnvConfig { runtime, items });
+    }
+    Ok(envs)
+}
+
+struct UniqueNames {
+    kind: &'static str,
+    owners: HashMap<String, String>,
+}
+
+impl UniqueNames {
+    fn new(kind: &'static str) -> UniqueNames {
+        UniqueNames {
+            kind,
+            owners: HashMap::new(),
+        }
+    }
+
+    fn insert(
+        &mut self,
+        value: &str,
+        owner: String,
+        source: &SourceFile,
+        span: &Range<usize>,
+    ) -> Result<(), AppError> {
+        if let Some(previous) = self.owners.get(value) {
+            return Err(source.error(
+                Some(span.clone()),
+                format!(
+                    "Duplicate {} '{}': {} conflicts with {}",
+                    self.kind, value, owner, previous
+                ),
+            ));
+        }
+        let owner = format!("{} at {}", owner, source.location(span));
+        self.owners.insert(value.to_string(), owner);
+        Ok(())
+    }
+}
+
 fn init_project_items(
     project_name: &str,
-    items: Option<&Vec<ProjectItemFileConfig>>,
+    source: &SourceFile,
+    project_span: Range<usize>,
+    items: Option<&Vec<Spanned<ProjectItemFileConfig>>>,
 ) -> Result<Vec<ProjectItemConfig>, AppError> {
     if items.is_none() {
+        return Err(source.error(
+            Some(project_span),
+            format!("No items defined for project '{}'", project_name),
+        ));
+    }
+
+    let mut items_config = Vec::new();
+    let mut item_names = UniqueNames::new("item name or alias");
+    let mut context_paths = UniqueNames::new("context path");
+    for item in items.unwrap() {
+        let span = item.span();
+        let item = item.get_ref();
+        let item_config = init_project_item(project_name, source.path.as_path(), item)
+            .map_err(|e| source.locate(span.clone(), e))?;
+
+        let owner = format!("item '{}' of project '{}'", item_config.name, project_name);
+        item_names.insert(&item_config.name, format!("name of {}", owner), source, &span)?;
+        if let Some(alias) = item_config.alias.as_ref().filter(|t| **t != item_config.name) {
+            item_names.insert(alias, format!("alias of {}", owner), source, &span)?;
+        }
+        let context_path = item_config.context_path.trim_end_matches('/');
+        let context_path = if context_path.is_empty() { "/" } else { context_path };
+        context_paths.insert(context_path, owner, source, &span)?;
+        items_config.push(item_config);
+    }
+    Ok(items_config)
+}
+
+fn init_project_item(
+    project_name: &str,
+    source: &Path,
+    item: &ProjectItemFileConfig,
+) -> Result<ProjectItemConfig, AppError> {
+    if item.name.is_none() {
         return Err(AppError::Config(format!(
-            "No items defined for project '{}'",
+            "No name defined for project '{}' item",
             project_name
         )));
     }
+    let name = item.name.as_ref().unwrap().clone();
+    if item.path.is_none() {
+        return Err(AppError::Config(format!(
+            "No path defined for project '{}' item '{}'",
+            project_name, &name
+        )));
+    }
 
-    let mut items_config = Vec::new();
-    for (i, item) in items.unwrap().iter().enumerate() {
-        if item.name.is_none() {
-            return Err(AppError::Config(format!(
-                "No name defined for project '{}' item {}",
+    let base_dir = source.parent().unwrap_or(Path::new(""));
+    let path = resolve_path(base_dir, expand("path", item.path.as_ref().unwrap())?);
+    let context_path = match expand_option("context-path", item.context_path.as_ref())? {
+        None => {
+            log::debug!(
+                "No context_path defined for project '{}' item '{}', falling back to '/'",
                 project_name,
-                i + 1
-            )));
+                &name
+            );
+            "/".to_string()
         }
-        let name = item.name.as_ref().unwrap().clone();
-        if item.path.is_none() {
+        Some(context_path) => {
+            if context_path.starts_with('/') {
+                context_path
+            } else {
+                log::info!(
+                    "context_path for project '{}' item '{}' does not start with '/'",
+                    project_name,
+                    &name
+                );
+                format!("/{}", context_path)
+            }
+        }
+    };
+    let context = init_item_context(project_name, &name, item, base_dir)?;
+    Ok(ProjectItemConfig {
+        name,
+        alias: item.alias.clone(),
+        path,
+        context_path,
+        context,
+        // located at the item by the caller
+        env: init_variables("env", item.env.as_ref(), &|_, e| e)?,
+        system_properties: init_variables(
+            "system-properties",
+            item.system_properties.as_ref(),
+            &|_, e| e,
+        )?,
+    })
+}
+
+fn init_item_context(
+    project_name: &str,
+    item_name: &str,
+    item: &ProjectItemFileConfig,
+    base_dir: &Path,
+) -> Result<ContextConfig, AppError> {
+    let mut context = ContextConfig {
+        reloadable: item.reloadable,
+        cross_context: item.cross_context,
+        cookies: item.cookies,
+        ..Default::default()
+    };
+
+    for (i, resource) in item.resources.iter().flatten().enumerate() {
+        let mut attributes = Vec::new();
+        for (key, value) in resource {
+            match expand_value(&format!("resource attribute '{}'", key), value)? {
+                Some(value) => attributes.push((key.clone(), value)),
+                None => {
+                    return Err(AppError::Config(format!(
+                        "Invalid value of '{}' for project '{}' item '{}' resource {}",
+                        key,
+                        project_name,
+                        item_name,
+                        i + 1
+                    )))
+                }
+            }
+        }
+        if !resource.contains_key("name") {
             return Err(AppError::Config(format!(
-                "No path defined for project '{}' item '{}'",
-                project_name, &name
+                "No name defined for project '{}' item '{}' resource {}",
+                project_name,
+                item_name,
+                i + 1
             )));
         }
+        context.resources.push(attributes);
+    }
 
-        let context_path = match item.context_path.as_ref() {
+    for (i, environment) in item.environments.iter().flatten().enumerate() {
+        let name = match &environment.name {
             None => {
-                log::debug!(
-                    "No context_path defined for project '{}' item '{}', falling back to '/'",
+                return Err(AppError::Config(format!(
+                    "No name defined for project '{}' item '{}' environment {}",
                     project_name,
-                    &name
-                );
-                "/".to_string()
-            }
-            Some(context_path) => {
-                if context_path.starts_with('/') {
-                    context_path.clone()
-                } else {
-                    log::info!(
-                        "context_path for project '{}' item '{}' does not start with '/'",
-                        project_name,
-                        &name
-                    );
-                    format!("/{}", context_path)
-                }
+                    item_name,
+                    i + 1
+                )))
             }
+            Some(name) => name.clone(),
         };
-        let item_config = ProjectItemConfig {
+        let value = match environment.value.as_ref() {
+            Some(value) => expand_value(&format!("environment '{}'", name), value)?,
+            None => None,
+        };
+        let value = match value {
+            None => {
+                return Err(AppError::Config(format!(
+                    "No valid value defined for project '{}' item '{}' environment '{}'",
+                    project_name, item_name, name
+                )))
+            }
+            Some(value) => value,
+        };
+        let value_type = match &environment.value_type {
+            Some(value_type) => value_type.clone(),
+            None => match environment.value.as_ref() {
+                Some(toml::Value::Integer(_)) => "java.lang.Integer".to_string(),
+                Some(toml::Value::Float(_)) => "java.lang.Double".to_string(),
+                Some(toml::Value::Boolean(_)) => "java.lang.Boolean".to_string(),
+                _ => "java.lang.String".to_string(),
+            },
+        };
+        context.environments.push(ContextEnvironmentConfig {
             name,
-            alias: item.alias.clone(),
-            path: item.path.as_ref().unwrap().clone(),
-            context_path,
+            value,
+            value_type,
+            override_value: environment.override_value,
+        });
+    }
+
+    for (i, parameter) in item.parameters.iter().flatten().enumerate() {
+        let name = match &parameter.name {
+            None => {
+                return Err(AppError::Config(format!(
+                    "No name defined for project '{}' item '{}' parameter {}",
+                    project_name,
+                    item_name,
+                    i + 1
+                )))
+            }
+            Some(name) => name.clone(),
         };
-        items_config.push(item_config);
+        let value = match parameter.value.as_ref() {
+            Some(value) => expand_value(&format!("parameter '{}'", name), value)?,
+            None => None,
+        };
+        let value = match value {
+            None => {
+                return Err(AppError::Config(format!(
+                    "No valid value defined for project '{}' item '{}' parameter '{}'",
+                    project_name, item_name, name
+                )))
+            }
+            Some(value) => value,
+        };
+        context.parameters.push(ContextParameterConfig {
+            name,
+            value,
+            override_value: parameter.override_value,
+        });
+    }
+
+    if let Some(web_resources) = &item.web_resources {
+        let location = format!("project '{}' item '{}'", project_name, item_name);
+        context.web_resources = Some(WebResourcesConfig {
+            caching_allowed: web_resources.caching_allowed,
+            cache_max_size: web_resources.cache_max_size,
+            cache_ttl: web_resources.cache_ttl,
+            pre: init_resource_sets(&location, "pre", web_resources.pre.as_ref(), base_dir)?,
+            jar: init_resource_sets(&location, "jar", web_resources.jar.as_ref(), base_dir)?,
+            post: init_resource_sets(&location, "post", web_resources.post.as_ref(), base_dir)?,
+        });
+    }
+
+    Ok(context)
+}
+
+fn init_resource_sets(
+    location: &str,
+    kind: &str,
+    resource_sets: Option<&Vec<ResourceSetFileConfig>>,
+    base_dir: &Path,
+) -> Result<Vec<ResourceSetConfig>, AppError> {
+    let mut configs = Vec::new();
+    for (i, resource_set) in resource_sets.into_iter().flatten().enumerate() {
+        let base = match &resource_set.base {
+            None => {
+                return Err(AppError::Config(format!(
+                    "No base defined for {} web resources {} {}",
+                    location,
+                    kind,
+                    i + 1
+                )))
+            }
+            Some(base) => resolve_path(base_dir, expand("base", base)?),
+        };
+        let class_name = match &resource_set.class_name {
+            Some(class_name) if class_name.contains('.') => class_name.clone(),
+            Some(class_name) => format!("{}.{}", WEB_RESOURCES_PACKAGE, class_name),
+            None if kind == "jar" || base.ends_with(".jar") => {
+                format!("{}.JarResourceSet", WEB_RESOURCES_PACKAGE)
+            }
+            None => format!("{}.DirResourceSet", WEB_RESOURCES_PACKAGE),
+        };
+        configs.push(ResourceSetConfig {
+            class_name,
+            base,
+            web_app_mount: expand_option("web-app-mount", resource_set.web_app_mount.as_ref())?,
+            internal_path: expand_option("internal-path", resource_set.internal_path.as_ref())?,
+            read_only: resource_set.read_only,
+        });
+    }
+    Ok(configs)
+}
+
+fn init_variables(
+    table: &str,
+    variables: Option<&BTreeMap<String, VariableFileConfig>>,
+    locate: Locate,
+) -> Result<BTreeMap<String, VariableConfig>, AppError> {
+    let mut configs = BTreeMap::new();
+    for (name, variable) in variables.into_iter().flatten() {
+        let config = init_variable(table, name, variable)
+            .map_err(|e| locate(&[table, name.as_str()], e))?;
+        configs.insert(name.clone(), config);
+    }
+    Ok(configs)
+}
+
+fn init_variable(
+    table: &str,
+    name: &str,
+    variable: &VariableFileConfig,
+) -> Result<VariableConfig, AppError> {
+    let field = format!("{} '{}'", table, name);
+    let config = match variable {
+        VariableFileConfig::File { file, base_dir } => {
+            VariableConfig::File(resolve_path(base_dir, expand(&field, file)?))
+        }
+        VariableFileConfig::Value(value) => match expand_value(&field, value)? {
+            Some(value) => VariableConfig::Value(value),
+            None => {
+                return Err(AppError::Config(format!(
+                    "Invalid value of '{}' in {}",
+                    name, table
+                )))
+            }
+        },
+    };
+    Ok(config)
+}
+
+// relative paths are resolved against the directory of the declaring file
+fn resolve_path(base_dir: &Path, path: String) -> String {
+    if Path::new(&path).is_absolute() {
+        path
+    } else {
+        base_dir.join(path).display().to_string()
+    }
+}
+
+fn expand_value(field: &str, value: &toml::Value) -> Result<Option<String>, AppError> {
+    match value {
+        toml::Value::String(value) => expand(field, value).map(Some),
+        value => Ok(value_to_string(value)),
+    }
+}
+
+fn value_to_string(value: &toml::Value) -> Option<String> {
+    match value {
+        toml::Value::String(value) => Some(value.clone()),
+        toml::Value::Integer(value) => Some(value.to_string()),
+        toml::Value::Float(value) => Some(value.to_string()),
+        toml::Value::Boolean(value) => Some(value.to_string()),
+        _ => None,
     }
-    Ok(items_config)
 }
@@ -4,8 +4,10 @@ pub use domain::*;
 pub use inspect::init;
 
 mod domain;
+mod expand;
 mod inspect;
 mod file;
+mod source;
 
 pub fn get_exe_directory() -> PathBuf {
     match std::env::current_exe() {
@@ -30,3 +32,10 @@ pub fn get_cache_dir() -> PathBuf {
         None => get_exe_directory().join("cache"),
     }
 }
+
+pub fn get_data_dir() -> PathBuf {
+    match dirs::data_dir() {
+        Some(dir) => dir.join("tms"),
+        None => get_exe_directory().join("data"),
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::fmt::Display;
+use std::ops::Range;
+use std::path::PathBuf;
+use std::rc::Rc;
+
+use crate::app::AppError;
+
+#[derive(Debug, Default)]
+pub struct SourceFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl SourceFile {
+    pub fn new(path: PathBuf, content: String) -> SourceFile {
+        SourceFile { path, content }
+    }
+
+    // 1-based line and column of the byte offset
+    pub fn position(&self, offset: usize) -> (usize, usize) {
+        let offset = offset.min(self.content.len());
+        let before = &self.content[..offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map(|t| t + 1).unwrap_or(0);
+        let column = self.content[line_start..offset].chars().count() + 1;
+        (line, column)
+    }
+
+    pub fn location(&self, span: &Range<usize>) -> String {
+        let (line, column) = self.position(span.start);
+        format!("{}:{}:{}", self.path.display(), line, column)
+    }
+
+    pub fn render<T: Display>(&self, span: Option<Range<usize>>, message: T) -> String {
+        let span = match span {
+            None => return format!("{}\n --> {}", message, self.path.display()),
+            Some(span) => span,
+        };
+        let (line, column) = self.position(span.start);
+        let text = self.content.lines().nth(line - 1).unwrap_or("");
+        let end = span.end.clamp(span.start, self.content.len());
+        let width = self.content[span.start.min(end)..end]
+            .lines()
+            .next()
+            .map(|t| t.chars().count())
+            .unwrap_or(0)
+            .max(1);
+        let gutter = " ".repeat(line.to_string().len());
+        format!(
+            "{}\n{}--> {}:{}:{}\n{} |\n{} | {}\n{} | {}{}",
+            message,
+            gutter,
+            self.path.display(),
+            line,
+            column,
+            gutter,
+            line,
+            text,
+            gutter,
+            " ".repeat(column - 1),
+            "^".repeat(width)
+        )
+    }
+
+    // attach the location to configuration errors raised without one
+    pub fn locate(&self, span: Range<usize>, error: AppError) -> AppError {
+        match error {
+            AppError::Config(message) => self.error(Some(span), message),
+            error => error,
+        }
+    }
+
+    pub fn error<T: Display>(&self, span: Option<Range<usize>>, message: T) -> AppError {
+        AppError::Located(self.render(span, message))
+    }
+}
+
+// spans of the keys below some top level tables, by key path. Tables declared only by
+// their sub tables cannot be spanned by serde, so the file is parsed again with toml_edit.
+#[derive(Debug, Default)]
+pub struct KeySpans {
+    spans: BTreeMap<Vec<String>, (Rc<SourceFile>, Range<usize>)>,
+}
+
+impl KeySpans {
+    pub fn collect(source: &Rc<SourceFile>, tables: &[&str]) -> KeySpans {
+        let mut key_spans = KeySpans::default();
+        let doc = match toml_edit::ImDocument::parse(source.content.as_str()) {
+            Ok(doc) => doc,
+            Err(_) => return key_spans,
+        };
+        let root = doc.as_table();
+        for table in tables {
+            if let Some(item) = root.get(table) {
+                let key_span = root.key(table).and_then(|t| t.span());
+                key_spans.add_item(source, vec![table.to_string()], key_span, item);
+            }
+        }
+        key_spans
+    }
+
+    // values point to themselves, tables to their header or to their key
+    fn add_item(
+        &mut self,
+        source: &Rc<SourceFile>,
+        path: Vec<String>,
+        key_span: Option<Range<usize>>,
+        item: &toml_edit::Item,
+    ) {
+        match item {
+            toml_edit::Item::Value(value) => self.add_value(source, path, key_span, value),
+            toml_edit::Item::Table(table) => {
+                self.insert(source, &path, table.span().or(key_span));
+                for (key, child) in table.iter() {
+                    let key_span = table.key(key).and_then(|t| t.span());
+                    let child_path = [path.as_slice(), &[key.to_string()]].concat();
+                    self.add_item(source, child_path, key_span, child);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add_value(
+        &mut self,
+        source: &Rc<SourceFile>,
+        path: Vec<String>,
+        key_span: Option<Range<usize>>,
+        value: &toml_edit::Value,
+    ) {
+        self.insert(source, &path, value.span().or(key_span));
+        if let Some(table) = value.as_inline_table() {
+            for (key, child) in table.iter() {
+                let key_span = table.key(key).and_then(|t| t.span());
+                let child_path = [path.as_slice(), &[key.to_string()]].concat();
+                self.add_value(source, child_path, key_span, child);
+            }
+        }
+    }
+
+    fn insert(&mut self, source: &Rc<SourceFile>, path: &[String], span: Option<Range<usize>>) {
+        if let Some(span) = span {
+            self.spans.insert(path.to_vec(), (source.clone(), span));
+        }
+    }
+
+    // spans of a file replace those of the files below it, like its keys do
+    pub fn merge(mut self, high: KeySpans) -> KeySpans {
+        self.spans.extend(high.spans);
+        self
+    }
+
+    // locate the error at the key, or at the closest table above it that has a span
+    pub fn locate(&self, keys: &[&str], error: AppError) -> AppError {
+        for len in (1..=keys.len()).rev() {
+            let path: Vec<String> = keys[..len].iter().map(|t| t.to_string()).collect();
+            if let Some((source, span)) = self.spans.get(&path) {
+                return source.locate(span.clone(), error);
+            }
+        }
+        error
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key_spans(content: &str) -> KeySpans {
+        let source = Rc::new(SourceFile::new(
+            PathBuf::from("t.toml"),
+            content.to_string(),
+        ));
+        KeySpans::collect(&source, &["default", "runtime"])
+    }
+
+    fn located_line(key_spans: &KeySpans, keys: &[&str]) -> Option<String> {
+        match key_spans.locate(keys, AppError::Config("e".to_string())) {
+            AppError::Located(message) => message.lines().nth(3).map(|t| t.to_string()),
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn locate_quoted_and_dotted_keys() {
+        let key_spans = key_spans(
+            "[runtime.\"jdk.17\".java]\njava_version = \"x\"\n\
+             [runtime]\ny.tomcat.tomcat_home = \"/t\"\n",
+        );
+        let line = located_line(&key_spans, &["runtime", "jdk.17", "java", "java_version"]);
+        assert_eq!(line.unwrap(), "2 | java_version = \"x\"");
+        let line = located_line(&key_spans, &["runtime", "y", "tomcat", "tomcat_home"]);
+        assert_eq!(line.unwrap(), "4 | y.tomcat.tomcat_home = \"/t\"");
+    }
+
+    #[test]
+    fn ignore_headers_in_strings() {
+        let key_spans = key_spans(
+            "note = \"\"\"\n[default.java]\n\"\"\"\n[default.java]\njava_home = \"/j\"\n",
+        );
+        let line = located_line(&key_spans, &["default", "java"]);
+        assert_eq!(line.unwrap(), "4 | [default.java]");
+    }
+
+    #[test]
+    fn locate_at_closest_table() {
+        let key_spans = key_spans("[default.env]\nA = \"a\"\n");
+        let line = located_line(&key_spans, &["default", "env", "B"]);
+        assert_eq!(line.unwrap(), "1 | [default.env]");
+        assert!(located_line(&key_spans, &["runtime", "x"]).is_none());
+    }
+
+    #[test]
+    fn higher_file_replaces_spans() {
+        let low = key_spans("[default.java]\njava_home = \"/low\"\n");
+        let high = key_spans("\n[default.java]\njava_home = \"/high\"\n");
+        let merged = low.merge(high);
+        let line = located_line(&merged, &["default", "java", "java_home"]);
+        assert_eq!(line.unwrap(), "3 | java_home = \"/high\"");
+    }
+}
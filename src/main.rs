@@ -10,19 +10,25 @@ fn main() {
     let config_path = args.config_path();
 
     match &args.action {
-        app::arg::Action::Config | app::arg::Action::Version => {
+        app::arg::Action::Config
+        | app::arg::Action::Version
+        | app::arg::Action::Jdks
+        | app::arg::Action::Tomcat(_) => {
+            // the logger is initialized with the config, so errors are printed directly
             if let Err(e) = app::run_without_config(args.action) {
-                log::error!("{}", e);
+                eprintln!("ERROR: {}", e);
                 std::process::exit(2);
             }
         }
         _ => match config::init(config_path) {
-            Ok(config) => {
-                if let Err(e) = app::run(&config) {
+            Ok(config) => match app::run(&config) {
+                Ok(0) => {}
+                Ok(code) => std::process::exit(code),
+                Err(e) => {
                     log::error!("{}", e);
                     std::process::exit(2);
                 }
-            }
+            },
             Err(e) => {
                 eprintln!("ERROR: {}", e);
                 std::process::exit(1);